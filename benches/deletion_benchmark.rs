@@ -25,6 +25,17 @@ fn create_benchmark_structure(temp_dir: &TempDir, num_dirs: usize) -> anyhow::Re
     Ok(())
 }
 
+/// Create a wide `vendor` tree (no node_modules inside) that exclusion patterns should skip
+fn create_excluded_subtree(temp_dir: &TempDir, num_dirs: usize) -> anyhow::Result<()> {
+    for i in 0..num_dirs {
+        let dir = temp_dir.path().join(format!("vendor/pkg_{}/src/lib", i));
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join("index.js"), "// vendored")?;
+    }
+
+    Ok(())
+}
+
 fn benchmark_cleanup(c: &mut Criterion) {
     let mut group = c.benchmark_group("cleanup_performance");
 
@@ -103,8 +114,68 @@ fn benchmark_scanning(c: &mut Criterion) {
                 });
             },
         );
+
+        group.bench_with_input(
+            BenchmarkId::new("parallel_directory_scanning", num_dirs),
+            num_dirs,
+            |b, &num_dirs| {
+                let temp_dir = TempDir::new().expect("Failed to create temp dir");
+                create_benchmark_structure(&temp_dir, num_dirs).expect("Failed to create structure");
+                let pool = rayon::ThreadPoolBuilder::new().build().expect("Failed to create thread pool");
+
+                b.iter(|| {
+                    let scanner = nuke_node_modules::scanner::Scanner::new(temp_dir.path(), &[]);
+                    scanner.find_node_modules_dirs_parallel(&pool).expect("Scanning failed")
+                });
+            },
+        );
     }
 
+    // A deep, wide tree without targets, where reading directories dominates
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    create_excluded_subtree(&temp_dir, 2000).expect("Failed to create tree");
+    let pool = rayon::ThreadPoolBuilder::new().build().expect("Failed to create thread pool");
+
+    group.bench_function("wide_tree_sequential", |b| {
+        b.iter(|| {
+            let scanner = nuke_node_modules::scanner::Scanner::new(temp_dir.path(), &[]);
+            scanner.find_node_modules_dirs().expect("Scanning failed")
+        });
+    });
+
+    group.bench_function("wide_tree_parallel", |b| {
+        b.iter(|| {
+            let scanner = nuke_node_modules::scanner::Scanner::new(temp_dir.path(), &[]);
+            scanner.find_node_modules_dirs_parallel(&pool).expect("Scanning failed")
+        });
+    });
+
+    // A large excluded subtree: pruned during the walk vs. walked and filtered afterwards
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    create_benchmark_structure(&temp_dir, 20).expect("Failed to create structure");
+    create_excluded_subtree(&temp_dir, 2000).expect("Failed to create excluded subtree");
+    let exclude_patterns = vec!["**/vendor/**".to_string()];
+
+    group.bench_function("excluded_subtree_pruned", |b| {
+        b.iter(|| {
+            let scanner = nuke_node_modules::scanner::Scanner::new(temp_dir.path(), &exclude_patterns);
+            scanner.find_node_modules_dirs().expect("Scanning failed")
+        });
+    });
+
+    group.bench_function("excluded_subtree_post_filtered", |b| {
+        let filter = nuke_node_modules::scanner::Scanner::new(temp_dir.path(), &exclude_patterns);
+        b.iter(|| {
+            let scanner = nuke_node_modules::scanner::Scanner::new(temp_dir.path(), &[]);
+            scanner
+                .find_node_modules_dirs()
+                .expect("Scanning failed")
+                .into_iter()
+                .filter(|path| !filter.should_exclude(path))
+                .collect::<Vec<_>>()
+        });
+    });
+
     group.finish();
 }
 
@@ -236,6 +236,7 @@ fn test_cli_binary_dry_run() -> Result<()> {
     temp_dir.child("test_project/node_modules/package.json").write_str("{}")?;
 
     let output = Command::new("cargo")
+        .env("XDG_STATE_HOME", temp_dir.path().join(".state"))
         .args([
             "run", "--",
             "--dry-run",
@@ -252,6 +253,167 @@ fn test_cli_binary_dry_run() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_cli_binary_reclaim_goal_out_of_reach() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    temp_dir.child("test_project/node_modules/package.json").write_str("{}")?;
+
+    let output = Command::new("cargo")
+        .env("XDG_STATE_HOME", temp_dir.path().join(".state"))
+        .args([
+            "run", "--",
+            "--dry-run",
+            "--reclaim", "1G",
+            temp_dir.path().to_str().unwrap()
+        ])
+        .output()?;
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Selected 1 directories"));
+    assert!(stdout.contains("Warning: the goal cannot be met"));
+    temp_dir.child("test_project/node_modules").assert(predicate::path::exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_binary_fast_mode() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    temp_dir.child("test_project/node_modules/package.json").write_str("{}")?;
+
+    let output = Command::new("cargo")
+        .env("XDG_STATE_HOME", temp_dir.path().join(".state"))
+        .args([
+            "run", "--",
+            "--fast",
+            "--no-confirm",
+            "--no-journal",
+            "--format",
+            "ndjson",
+            temp_dir.path().to_str().unwrap()
+        ])
+        .output()?;
+
+    assert!(output.status.success());
+    temp_dir.child("test_project/node_modules").assert(predicate::path::missing());
+    // Renamed aside is not deleted yet, and is reported as such
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(r#""status":"staged""#));
+    assert!(stdout.contains(r#""directories_staged":1"#));
+
+    // The renamed tree is removed by a detached background process
+    let project = temp_dir.child("test_project");
+    for _ in 0..100 {
+        if std::fs::read_dir(project.path())?.next().is_none() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    assert_eq!(std::fs::read_dir(project.path())?.count(), 0);
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_cli_binary_reinstall() -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = TempDir::new()?;
+    temp_dir.child("app/node_modules/stale/index.js").write_str("stale")?;
+    temp_dir.child("app/package-lock.json").write_str("{}")?;
+
+    // A stub npm that records its arguments and installs a fresh tree
+    let npm = temp_dir.child("bin/npm");
+    npm.write_str("#!/bin/sh\necho \"$@\" > installed-with\nmkdir -p node_modules/fresh\n")?;
+    std::fs::set_permissions(npm.path(), std::fs::Permissions::from_mode(0o755))?;
+
+    let path = std::env::join_paths(
+        std::iter::once(temp_dir.child("bin").to_path_buf())
+            .chain(std::env::split_paths(&std::env::var_os("PATH").unwrap_or_default())),
+    )?;
+    let output = Command::new("cargo")
+        .env("XDG_STATE_HOME", temp_dir.path().join(".state"))
+        .args([
+            "run", "--",
+            "--reinstall",
+            "--no-confirm",
+            "--no-journal",
+            "--format", "json",
+            temp_dir.path().to_str().unwrap()
+        ])
+        .env("PATH", path)
+        .output()?;
+
+    assert!(output.status.success());
+    temp_dir.child("app/node_modules/stale").assert(predicate::path::missing());
+    temp_dir.child("app/node_modules/fresh").assert(predicate::path::is_dir());
+    temp_dir.child("app/installed-with").assert("ci\n");
+
+    let document: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(document["stats"]["reinstalls"][0]["manager"], "npm");
+    assert_eq!(document["stats"]["reinstalls"][0]["status"], "installed");
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_binary_json_output() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    temp_dir.child("test_project/node_modules/package.json").write_str("{}")?;
+
+    let output = Command::new("cargo")
+        .env("XDG_STATE_HOME", temp_dir.path().join(".state"))
+        .args([
+            "run", "--",
+            "--dry-run",
+            "--format", "json",
+            temp_dir.path().to_str().unwrap()
+        ])
+        .output()?;
+
+    assert!(output.status.success());
+
+    let document: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(document["stats"]["directories_found"], 1);
+    assert_eq!(document["targets"][0]["status"], "would_delete");
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_binary_history_json_output() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let project = temp_dir.child("projects/test_project");
+    project.child("node_modules/package.json").write_str("{}")?;
+    let state_home = temp_dir.path().join("state");
+
+    let output = Command::new("cargo")
+        .env("XDG_STATE_HOME", &state_home)
+        .args([
+            "run", "--",
+            "--no-confirm",
+            "--quiet",
+            temp_dir.child("projects").path().to_str().unwrap()
+        ])
+        .output()?;
+    assert!(output.status.success());
+
+    let output = Command::new("cargo")
+        .env("XDG_STATE_HOME", &state_home)
+        .args(["run", "--", "history", "--format", "json"])
+        .output()?;
+    assert!(output.status.success());
+
+    let runs: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(runs.as_array().map(Vec::len), Some(1));
+    assert_eq!(runs[0]["deleted"], 1);
+    project.child("node_modules").assert(predicate::path::missing());
+
+    Ok(())
+}
+
 #[test]
 fn test_invalid_exclusion_pattern_handling() -> Result<()> {
     let temp_dir = TempDir::new()?;
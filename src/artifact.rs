@@ -0,0 +1,136 @@
+//! Kinds of build-artifact directories that can be cleaned up
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// A kind of regenerable directory, identified by its name and a marker file in its parent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, clap::ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ArtifactKind {
+    /// npm/yarn/pnpm dependencies (`node_modules`)
+    NodeModules,
+    /// Next.js build output (`.next`)
+    Next,
+    /// Turborepo cache (`.turbo`)
+    Turbo,
+    /// JavaScript bundler output (`dist`)
+    Dist,
+    /// Parcel cache (`.parcel-cache`)
+    ParcelCache,
+    /// Bower dependencies (`bower_components`)
+    BowerComponents,
+    /// Cargo build output (`target`)
+    Target,
+    /// Python virtual environment (`.venv`)
+    Venv,
+    /// Yarn Plug'n'Play package cache (`.yarn/cache`)
+    YarnCache,
+    /// Yarn Plug'n'Play loader (the `.pnp.cjs` file)
+    Pnp,
+}
+
+impl ArtifactKind {
+    /// Every supported kind
+    pub const ALL: &'static [ArtifactKind] = &[
+        ArtifactKind::NodeModules,
+        ArtifactKind::Next,
+        ArtifactKind::Turbo,
+        ArtifactKind::Dist,
+        ArtifactKind::ParcelCache,
+        ArtifactKind::BowerComponents,
+        ArtifactKind::Target,
+        ArtifactKind::Venv,
+        ArtifactKind::YarnCache,
+        ArtifactKind::Pnp,
+    ];
+
+    /// Path of the directory (or file) this kind produces, relative to its project
+    pub fn dir_name(self) -> &'static str {
+        match self {
+            ArtifactKind::NodeModules => "node_modules",
+            ArtifactKind::Next => ".next",
+            ArtifactKind::Turbo => ".turbo",
+            ArtifactKind::Dist => "dist",
+            ArtifactKind::ParcelCache => ".parcel-cache",
+            ArtifactKind::BowerComponents => "bower_components",
+            ArtifactKind::Target => "target",
+            ArtifactKind::Venv => ".venv",
+            ArtifactKind::YarnCache => ".yarn/cache",
+            ArtifactKind::Pnp => ".pnp.cjs",
+        }
+    }
+
+    /// Whether this kind is a single file rather than a directory
+    pub fn is_file(self) -> bool {
+        self == ArtifactKind::Pnp
+    }
+
+    /// Files of which at least one must exist next to the directory (empty = no marker required)
+    pub fn markers(self) -> &'static [&'static str] {
+        match self {
+            ArtifactKind::NodeModules => &[],
+            ArtifactKind::Next | ArtifactKind::Dist | ArtifactKind::ParcelCache => &["package.json"],
+            ArtifactKind::Turbo => &["turbo.json", "package.json"],
+            ArtifactKind::BowerComponents => &["bower.json"],
+            ArtifactKind::Target => &["Cargo.toml"],
+            ArtifactKind::Venv => &["pyproject.toml", "requirements.txt", "setup.py", "setup.cfg", "Pipfile"],
+            ArtifactKind::YarnCache | ArtifactKind::Pnp => &["yarn.lock"],
+        }
+    }
+
+    /// Check whether `path` is a directory of this kind (by name and project marker)
+    pub fn matches(self, path: &Path) -> bool {
+        if !path.ends_with(self.dir_name()) {
+            return false;
+        }
+
+        let markers = self.markers();
+        markers.is_empty()
+            || self
+                .project_dir(path)
+                .is_some_and(|project| markers.iter().any(|marker| project.join(marker).is_file()))
+    }
+
+    /// The project a directory of this kind belongs to, e.g. `app` for `app/.yarn/cache`
+    pub fn project_dir(self, path: &Path) -> Option<&Path> {
+        path.ancestors().nth(Path::new(self.dir_name()).components().count())
+    }
+
+    /// Identify which kind a path belongs to, if any
+    pub fn from_path(path: &Path) -> Option<ArtifactKind> {
+        Self::ALL.iter().copied().find(|kind| kind.matches(path))
+    }
+
+    /// Check whether a directory name belongs to any known kind
+    pub fn is_artifact_name(name: &str) -> bool {
+        Self::ALL.iter().any(|kind| kind.dir_name() == name)
+    }
+
+    /// Check whether a path ends like a directory of any known kind, ignoring markers
+    pub fn is_artifact_path(path: &Path) -> bool {
+        Self::ALL.iter().any(|kind| path.ends_with(kind.dir_name()))
+    }
+}
+
+/// The project a target belongs to: usually its parent, or e.g. `app` for `app/.yarn/cache`
+pub fn project_of(target: &Path) -> &Path {
+    ArtifactKind::ALL
+        .iter()
+        .filter(|kind| target.ends_with(kind.dir_name()))
+        .find_map(|kind| kind.project_dir(target))
+        .or_else(|| target.parent())
+        .unwrap_or(target)
+}
+
+/// Describe a set of kinds for messages, e.g. `node_modules` or `node_modules/target`
+pub fn describe(kinds: &[ArtifactKind]) -> String {
+    if kinds.is_empty() {
+        return ArtifactKind::NodeModules.dir_name().to_string();
+    }
+
+    kinds
+        .iter()
+        .map(|kind| kind.dir_name())
+        .collect::<Vec<_>>()
+        .join("/")
+}
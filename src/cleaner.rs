@@ -1,18 +1,251 @@
 //! Parallel directory deletion functionality
 
-use crate::{scanner, CleanupStats, format_bytes};
+use crate::artifact::project_of;
+use crate::in_use::ProcessSnapshot;
+use crate::journal::Journal;
+use crate::report::{self, OutputFormat, TargetOutcome, TargetStatus};
+use crate::{scanner, staging, trash::Trash, CleanupStats, NukeError, format_bytes};
 use anyhow::Result;
 use indicatif::{ProgressBar, ProgressStyle};
 use rayon::prelude::*;
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+/// Size and age of a target, measured before deletion
+#[derive(Debug, Clone)]
+pub struct TargetInfo {
+    /// Path of the target directory
+    pub path: PathBuf,
+    /// Disk space allocated to the directory in bytes
+    pub bytes: u64,
+    /// Sum of the file lengths in the directory in bytes
+    pub apparent_bytes: u64,
+    /// Time since the owning project was last used, if known
+    pub age: Option<Duration>,
+}
+
+/// Size of a directory tree, both as file lengths and as allocated disk blocks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiskUsage {
+    /// Sum of file lengths (what `ls -l` shows)
+    pub apparent: u64,
+    /// Space actually allocated on disk (what `du` shows)
+    pub allocated: u64,
+}
+
+impl std::ops::AddAssign for DiskUsage {
+    fn add_assign(&mut self, other: Self) {
+        self.apparent += other.apparent;
+        self.allocated += other.allocated;
+    }
+}
+
+impl DiskUsage {
+    /// Space released between two measurements of the same tree
+    fn freed_since(self, before: DiskUsage) -> DiskUsage {
+        DiskUsage {
+            apparent: before.apparent.saturating_sub(self.apparent),
+            allocated: before.allocated.saturating_sub(self.allocated),
+        }
+    }
+}
+
+/// Thread-safe counters and outcomes of one deletion run
+struct DeletionRun {
+    progress_bar: Option<ProgressBar>,
+    deleted: AtomicUsize,
+    trashed: AtomicUsize,
+    failed: AtomicUsize,
+    partial: AtomicUsize,
+    in_use: AtomicUsize,
+    cancelled: AtomicUsize,
+    bytes_freed: AtomicU64,
+    bytes_freed_apparent: AtomicU64,
+    bytes_trashed: AtomicU64,
+    bytes_trashed_apparent: AtomicU64,
+    bytes_staged: AtomicU64,
+    inodes: InodeTracker,
+    processes: Option<ProcessSnapshot>,
+    journal: Option<Arc<Journal>>,
+    staged: Mutex<Vec<PathBuf>>,
+    outcomes: Mutex<Vec<TargetOutcome>>,
+}
+
+impl DeletionRun {
+    fn new(progress_bar: Option<ProgressBar>, processes: Option<ProcessSnapshot>, journal: Option<Arc<Journal>>) -> Self {
+        Self {
+            progress_bar,
+            deleted: AtomicUsize::new(0),
+            trashed: AtomicUsize::new(0),
+            failed: AtomicUsize::new(0),
+            partial: AtomicUsize::new(0),
+            in_use: AtomicUsize::new(0),
+            cancelled: AtomicUsize::new(0),
+            bytes_freed: AtomicU64::new(0),
+            bytes_freed_apparent: AtomicU64::new(0),
+            bytes_trashed: AtomicU64::new(0),
+            bytes_trashed_apparent: AtomicU64::new(0),
+            bytes_staged: AtomicU64::new(0),
+            inodes: InodeTracker::new(),
+            processes,
+            journal,
+            staged: Mutex::new(Vec::new()),
+            outcomes: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn add_freed(&self, usage: DiskUsage) {
+        self.bytes_freed.fetch_add(usage.allocated, Ordering::Relaxed);
+        self.bytes_freed_apparent.fetch_add(usage.apparent, Ordering::Relaxed);
+    }
+
+    fn add_trashed(&self, usage: DiskUsage) {
+        self.bytes_trashed.fetch_add(usage.allocated, Ordering::Relaxed);
+        self.bytes_trashed_apparent.fetch_add(usage.apparent, Ordering::Relaxed);
+    }
+
+    /// Count an outcome, stream it if requested and keep it for the final report
+    fn record(&self, output: OutputFormat, outcome: TargetOutcome) {
+        if let Some(pb) = &self.progress_bar {
+            pb.inc(1);
+        }
+
+        match outcome.status {
+            TargetStatus::Deleted => self.deleted.fetch_add(1, Ordering::Relaxed),
+            TargetStatus::Trashed => self.trashed.fetch_add(1, Ordering::Relaxed),
+            TargetStatus::Cancelled => self.cancelled.fetch_add(1, Ordering::Relaxed),
+            TargetStatus::InUse => self.in_use.fetch_add(1, Ordering::Relaxed),
+            TargetStatus::Failed => self.failed.fetch_add(1, Ordering::Relaxed),
+            TargetStatus::PartiallyDeleted => {
+                self.partial.fetch_add(1, Ordering::Relaxed);
+                self.failed.fetch_add(1, Ordering::Relaxed)
+            }
+            // Staged targets are counted by the trees kept for the background deletion
+            TargetStatus::Staged | TargetStatus::WouldDelete | TargetStatus::Restored | TargetStatus::WouldRestore => 0,
+        };
+
+        if let Some(journal) = &self.journal {
+            journal.record_outcome(&outcome);
+        }
+
+        if output == OutputFormat::Ndjson {
+            let _ = report::emit_target(&outcome);
+        }
+
+        if let Ok(mut outcomes) = self.outcomes.lock() {
+            outcomes.push(outcome);
+        }
+    }
+}
+
+/// Result of trying to remove one target
+enum Removal {
+    /// The target is gone
+    Removed(DiskUsage),
+    /// The target was renamed aside and still has to be deleted
+    Staged(DiskUsage, PathBuf),
+    /// Removal failed after freeing part of the target
+    Partial(DiskUsage, NukeError),
+    /// Removal failed and the target is untouched
+    Failed(NukeError),
+}
+
+/// Remembers (device, inode) pairs so hardlinked files are only counted once per run
+#[derive(Debug, Default)]
+pub struct InodeTracker {
+    seen: RwLock<HashSet<(u64, u64)>>,
+    any_seen: AtomicBool,
+}
+
+impl InodeTracker {
+    /// Create an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true the first time a file is seen
+    ///
+    /// Only hardlinked files are remembered. A file whose other links were already
+    /// deleted during this run still shows up as seen, even though its link count dropped.
+    pub fn first_sighting(&self, metadata: &std::fs::Metadata) -> bool {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+
+            let key = (metadata.dev(), metadata.ino());
+            if metadata.nlink() > 1 {
+                self.any_seen.store(true, Ordering::Relaxed);
+                return self.seen.write().map(|mut seen| seen.insert(key)).unwrap_or(true);
+            }
+            if self.any_seen.load(Ordering::Relaxed) {
+                return self.seen.read().map(|seen| !seen.contains(&key)).unwrap_or(true);
+            }
+        }
+
+        #[cfg(not(unix))]
+        let _ = metadata;
+
+        true
+    }
+}
+
+/// Order in which targets are listed and deleted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    /// Alphabetically by path
+    #[default]
+    Path,
+    /// Largest first
+    Size,
+    /// Least recently used project first
+    Age,
+}
+
+impl std::fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SortOrder::Path => write!(f, "path"),
+            SortOrder::Size => write!(f, "size"),
+            SortOrder::Age => write!(f, "age"),
+        }
+    }
+}
+
+/// Sort measured targets; ties (and unknown ages) fall back to path order
+pub fn sort_targets(targets: &mut [TargetInfo], order: SortOrder) {
+    match order {
+        SortOrder::Path => targets.sort_by(|a, b| a.path.cmp(&b.path)),
+        SortOrder::Size => targets.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path))),
+        SortOrder::Age => targets.sort_by(|a, b| {
+            // Oldest first, unknown ages last
+            let a_age = a.age.map(std::cmp::Reverse);
+            let b_age = b.age.map(std::cmp::Reverse);
+            a_age
+                .is_none()
+                .cmp(&b_age.is_none())
+                .then_with(|| a_age.cmp(&b_age))
+                .then_with(|| a.path.cmp(&b.path))
+        }),
+    }
+}
 
 /// Cleaner for parallel directory deletion
 pub struct Cleaner {
     thread_pool: rayon::ThreadPool,
     show_progress: bool,
+    trash: Option<Trash>,
+    output: OutputFormat,
+    cancel_flag: Option<Arc<AtomicBool>>,
+    root: Option<PathBuf>,
+    delete_symlinked: bool,
+    skip_in_use: bool,
+    fast: bool,
+    journal: Option<Arc<Journal>>,
 }
 
 
@@ -32,9 +265,81 @@ impl Cleaner {
         Self {
             thread_pool,
             show_progress,
+            trash: None,
+            output: OutputFormat::Text,
+            cancel_flag: None,
+            root: None,
+            delete_symlinked: false,
+            skip_in_use: false,
+            fast: false,
+            journal: None,
         }
     }
 
+    /// Stream per-target outcomes in the given format as deletions complete
+    pub fn with_output(mut self, output: OutputFormat) -> Self {
+        self.output = output;
+        self
+    }
+
+    /// Move directories into the given trash instead of deleting them
+    pub fn with_trash(mut self, trash: Option<Trash>) -> Self {
+        self.trash = trash;
+        self
+    }
+
+    /// Stop starting new deletions once `flag` is set; in-flight deletions still finish
+    pub fn with_cancel_flag(mut self, flag: Option<Arc<AtomicBool>>) -> Self {
+        self.cancel_flag = flag;
+        self
+    }
+
+    /// Refuse to delete anything that resolves outside `root`
+    ///
+    /// Targets that do are recorded as failed; the others are still deleted.
+    pub fn with_root(mut self, root: Option<PathBuf>) -> Self {
+        self.root = root;
+        self
+    }
+
+    /// Delete what a symlinked target points to, instead of only removing the link
+    pub fn with_delete_symlinked(mut self, delete_symlinked: bool) -> Self {
+        self.delete_symlinked = delete_symlinked;
+        self
+    }
+
+    /// Skip targets that a running process has open (detected on Linux only)
+    pub fn with_skip_in_use(mut self, skip_in_use: bool) -> Self {
+        self.skip_in_use = skip_in_use;
+        self
+    }
+
+    /// Rename targets aside instead of deleting them, leaving the deletion to the caller
+    ///
+    /// The renamed trees are listed in [`CleanupStats::pending_deletion`]; see [`staging`].
+    pub fn with_fast(mut self, fast: bool) -> Self {
+        self.fast = fast;
+        self
+    }
+
+    /// Record every target before it is removed, and its outcome afterwards, in `journal`
+    pub fn with_journal(mut self, journal: Option<Arc<Journal>>) -> Self {
+        self.journal = journal;
+        self
+    }
+
+    /// Whether cancellation has been requested
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel_flag
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::SeqCst))
+    }
+
+    /// Thread pool shared by scanning, measuring and deletion
+    pub fn thread_pool(&self) -> &rayon::ThreadPool {
+        &self.thread_pool
+    }
+
     /// Check if we're running in a test environment
     pub fn is_test_environment() -> bool {
         // Compile-time test detection
@@ -50,9 +355,29 @@ impl Cleaner {
         )
     }
 
+    /// Measure the size and project age of each target in parallel
+    pub fn measure_targets(&self, targets: &[PathBuf]) -> Vec<TargetInfo> {
+        let inodes = InodeTracker::new();
+
+        self.thread_pool.install(|| {
+            targets
+                .par_iter()
+                .map(|target| {
+                    let usage = calculate_disk_usage(&self.contents_of(target), &inodes).unwrap_or_default();
+                    TargetInfo {
+                        path: target.clone(),
+                        bytes: usage.allocated,
+                        apparent_bytes: usage.apparent,
+                        age: scanner::project_age(target),
+                    }
+                })
+                .collect()
+        })
+    }
+
     /// Delete directories in parallel
     pub fn delete_directories(&self, targets: Vec<PathBuf>) -> Result<CleanupStats> {
-        // Safety check - ensure all paths end with node_modules
+        // Safety check - ensure all paths are recognised artifact directories
         scanner::validate_targets(&targets)?;
 
         if targets.is_empty() {
@@ -71,79 +396,395 @@ impl Cleaner {
             None
         };
 
-        // Atomic counters for thread-safe statistics
-        let deleted_count = AtomicUsize::new(0);
-        let failed_count = AtomicUsize::new(0);
-        let bytes_freed = AtomicU64::new(0);
-        let errors = Mutex::new(Vec::new());
+        if let Some(journal) = &self.journal {
+            journal.record_pending(&targets);
+        }
+        let run = DeletionRun::new(progress_bar, self.capture_processes(), self.journal.clone());
 
         // Execute deletions in parallel
         self.thread_pool.install(|| {
-            targets
-                .par_iter()
-                .for_each(|target| {
-                    let result = self.delete_single_directory(target);
+            targets.par_iter().for_each(|target| match self.validate_location(target) {
+                Ok(()) => self.process_target(&run, target),
+                Err(e) => self.refuse(&run, target, e),
+            });
+        });
 
-                    // Update progress bar
-                    if let Some(ref pb) = progress_bar {
-                        pb.inc(1);
-                    }
+        Ok(self.finish_run(run, targets.len()))
+    }
+
+    /// Delete targets as they arrive, e.g. from [`Scanner::scan_stream`](scanner::Scanner::scan_stream)
+    ///
+    /// Deletion starts while the scan is still running. Each target is validated on arrival;
+    /// invalid ones are recorded as failed. A scan error stops new deletions and is returned
+    /// once in-flight ones finish.
+    pub fn delete_stream<I>(&self, targets: I) -> Result<CleanupStats>
+    where
+        I: Iterator<Item = Result<PathBuf>> + Send,
+    {
+        let progress_bar = if self.show_progress {
+            let pb = ProgressBar::new_spinner();
+            pb.set_style(ProgressStyle::default_spinner().template("{spinner:.green} [{elapsed_precise}] {pos} processed {msg}")?);
+            pb.set_message("(still scanning)");
+            Some(pb)
+        } else {
+            None
+        };
+
+        let run = DeletionRun::new(progress_bar, self.capture_processes(), self.journal.clone());
+        let found = AtomicUsize::new(0);
+        let scan_error = Mutex::new(None);
 
-                    // Update counters
-                    match result {
-                        Ok(bytes) => {
-                            deleted_count.fetch_add(1, Ordering::Relaxed);
-                            bytes_freed.fetch_add(bytes, Ordering::Relaxed);
+        self.thread_pool.install(|| {
+            targets.par_bridge().for_each(|target| {
+                let target = match target {
+                    Ok(target) => target,
+                    Err(e) => {
+                        if let Ok(mut scan_error) = scan_error.lock() {
+                            scan_error.get_or_insert(e);
                         }
-                        Err(e) => {
-                            failed_count.fetch_add(1, Ordering::Relaxed);
-                            if let Ok(mut errors) = errors.lock() {
-                                errors.push(format!("{}: {}", target.display(), e));
-                            }
+                        return;
+                    }
+                };
+                found.fetch_add(1, Ordering::Relaxed);
+
+                if scan_error.lock().is_ok_and(|e| e.is_some()) {
+                    return;
+                }
+                let valid = scanner::validate_targets(std::slice::from_ref(&target))
+                    .and_then(|()| self.validate_location(&target));
+                match valid {
+                    Ok(()) => {
+                        if let Some(journal) = &self.journal {
+                            journal.record_pending(std::slice::from_ref(&target));
                         }
+                        self.process_target(&run, &target)
                     }
-                })
+                    Err(e) => self.refuse(&run, &target, e),
+                }
+            });
         });
 
-        if let Some(pb) = progress_bar {
-            pb.finish_with_message("Cleanup complete!");
+        let stats = self.finish_run(run, found.into_inner());
+        match scan_error.into_inner().ok().flatten() {
+            Some(e) => Err(e.context(format!(
+                "Scan failed after {} directories were processed",
+                stats.directories_deleted + stats.directories_trashed + stats.directories_staged
+            ))),
+            None => Ok(stats),
+        }
+    }
+
+    /// Delete or trash one target and record the outcome
+    fn process_target(&self, run: &DeletionRun, target: &Path) {
+        // Once interrupted, record the remaining targets without touching them
+        if self.is_cancelled() {
+            return run.record(self.output, TargetOutcome::cancelled(target.to_path_buf()));
+        }
+
+        // Only machine-readable output shows the age, and finding it walks the project
+        let age_secs = self
+            .output
+            .is_machine_readable()
+            .then(|| scanner::project_age(target))
+            .flatten()
+            .map(|age| age.as_secs());
+
+        if let Some(process) = run.processes.as_ref().and_then(|processes| processes.user_of(target)) {
+            return run.record(self.output, TargetOutcome {
+                path: target.to_path_buf(),
+                bytes: None,
+                apparent_bytes: None,
+                age_secs,
+                status: TargetStatus::InUse,
+                error: Some(NukeError::Busy {
+                    path: target.to_path_buf(),
+                    reason: format!("in use by {}", process),
+                }),
+            });
+        }
+
+        let result = self.remove_target(target, &run.inodes);
+
+        // Update counters
+        let outcome = match result {
+            Removal::Staged(usage, staged) => {
+                // Only freed once the background deletion gets to it
+                run.bytes_staged.fetch_add(usage.allocated, Ordering::Relaxed);
+                if let Ok(mut pending) = run.staged.lock() {
+                    pending.push(staged);
+                }
+
+                TargetOutcome {
+                    path: target.to_path_buf(),
+                    bytes: Some(usage.allocated),
+                    apparent_bytes: Some(usage.apparent),
+                    age_secs,
+                    status: TargetStatus::Staged,
+                    error: None,
+                }
+            }
+            Removal::Removed(usage) => {
+                // A trashed tree still takes up its space until the trash is emptied
+                let status = if self.trash.is_some() {
+                    run.add_trashed(usage);
+                    TargetStatus::Trashed
+                } else {
+                    run.add_freed(usage);
+                    TargetStatus::Deleted
+                };
+
+                TargetOutcome {
+                    path: target.to_path_buf(),
+                    bytes: Some(usage.allocated),
+                    apparent_bytes: Some(usage.apparent),
+                    age_secs,
+                    status,
+                    error: None,
+                }
+            }
+            Removal::Partial(usage, e) => {
+                run.add_freed(usage);
+
+                TargetOutcome {
+                    path: target.to_path_buf(),
+                    bytes: Some(usage.allocated),
+                    apparent_bytes: Some(usage.apparent),
+                    age_secs,
+                    status: TargetStatus::PartiallyDeleted,
+                    error: Some(e.partially_deleted()),
+                }
+            }
+            Removal::Failed(e) => TargetOutcome {
+                path: target.to_path_buf(),
+                bytes: None,
+                apparent_bytes: None,
+                age_secs,
+                status: TargetStatus::Failed,
+                error: Some(e),
+            },
+        };
+
+        run.record(self.output, outcome);
+    }
+
+    /// Print errors and the summary of a run and turn it into statistics
+    fn finish_run(&self, run: DeletionRun, found: usize) -> CleanupStats {
+        if let Some(pb) = &run.progress_bar {
+            if self.is_cancelled() {
+                pb.abandon_with_message("Cleanup interrupted");
+            } else {
+                pb.finish_with_message("Cleanup complete!");
+            }
         }
 
+        let mut outcomes = run.outcomes.into_inner().unwrap_or_default();
+        outcomes.sort_by(|a, b| a.path.cmp(&b.path));
+
         // Print errors if any occurred
-        if let Ok(error_list) = errors.lock()
-            && !error_list.is_empty() && self.show_progress {
+        let mut errors = outcomes.iter().filter_map(|outcome| outcome.error.as_ref()).peekable();
+        if errors.peek().is_some() && self.show_progress {
             eprintln!("\nErrors encountered:");
-            for error in error_list.iter() {
+            for error in errors {
                 eprintln!("  {}", error);
             }
         }
 
+        let pending_deletion = run.staged.into_inner().unwrap_or_default();
         let stats = CleanupStats {
-            directories_found: targets.len(),
-            directories_deleted: deleted_count.load(Ordering::Relaxed),
-            directories_failed: failed_count.load(Ordering::Relaxed),
-            bytes_freed: bytes_freed.load(Ordering::Relaxed),
+            directories_found: found,
+            directories_selected: found,
+            directories_deleted: run.deleted.into_inner(),
+            directories_trashed: run.trashed.into_inner(),
+            directories_staged: pending_deletion.len(),
+            directories_failed: run.failed.into_inner(),
+            directories_partial: run.partial.into_inner(),
+            directories_in_use: run.in_use.into_inner(),
+            directories_cancelled: run.cancelled.into_inner(),
+            bytes_freed: run.bytes_freed.into_inner(),
+            bytes_freed_apparent: run.bytes_freed_apparent.into_inner(),
+            bytes_trashed: run.bytes_trashed.into_inner(),
+            bytes_trashed_apparent: run.bytes_trashed_apparent.into_inner(),
+            bytes_pending_deletion: run.bytes_staged.into_inner(),
+            targets: outcomes,
+            pending_deletion,
+            ..Default::default()
         };
 
         if self.show_progress {
             print_cleanup_summary(&stats);
         }
 
-        Ok(stats)
+        stats
     }
 
-    /// Delete a single directory and return bytes freed
+    /// Delete a single directory and return the disk space freed
     pub fn delete_single_directory(&self, path: &Path) -> Result<u64> {
+        Ok(self.delete_with_usage(path, &InodeTracker::new()).into_result()?.allocated)
+    }
+
+    /// Move a single directory into the trash and return the disk space it occupied
+    pub fn trash_single_directory(&self, path: &Path, trash: &Trash) -> Result<u64> {
+        Ok(self.trash_with_usage(path, trash, &InodeTracker::new()).into_result()?.allocated)
+    }
+
+    /// Snapshot running processes when busy targets are to be skipped
+    fn capture_processes(&self) -> Option<ProcessSnapshot> {
+        self.skip_in_use.then(ProcessSnapshot::capture)
+    }
+
+    /// Check that a target doesn't escape the root
+    ///
+    /// Without a root, a target that would be deleted through a symlink must at least point
+    /// inside its own project.
+    fn validate_location(&self, target: &Path) -> Result<(), NukeError> {
+        let through_symlinks = self.delete_symlinked && self.trash.is_none();
+        let targets = [target.to_path_buf()];
+        match &self.root {
+            Some(root) => scanner::validate_within_root(&targets, root, through_symlinks),
+            None if through_symlinks && is_symlink(target) => {
+                let project = match project_of(target) {
+                    project if project.as_os_str().is_empty() => Path::new("."),
+                    project => project,
+                };
+                scanner::validate_within_root(&targets, project, true)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Record a target that failed a safety check as failed, without touching it
+    fn refuse(&self, run: &DeletionRun, target: &Path, error: NukeError) {
+        run.record(self.output, TargetOutcome {
+            path: target.to_path_buf(),
+            bytes: None,
+            apparent_bytes: None,
+            age_secs: None,
+            status: TargetStatus::Failed,
+            error: Some(error),
+        })
+    }
+
+    /// The directory whose contents removing a target frees
+    ///
+    /// That is the target itself, unless it is a symlink that will be deleted through.
+    fn contents_of(&self, target: &Path) -> PathBuf {
+        if self.delete_symlinked && self.trash.is_none() && is_symlink(target) {
+            fs::canonicalize(target).unwrap_or_else(|_| target.to_path_buf())
+        } else {
+            target.to_path_buf()
+        }
+    }
+
+    /// Delete or trash a target depending on the configured mode
+    fn remove_target(&self, path: &Path, inodes: &InodeTracker) -> Removal {
+        match &self.trash {
+            Some(trash) => self.trash_with_usage(path, trash, inodes),
+            // Links and single files are removed instantly anyway, so only real directories are staged
+            None if self.fast && path.symlink_metadata().is_ok_and(|m| m.is_dir()) => stage_with_usage(path, inodes),
+            None => self.delete_with_usage(path, inodes),
+        }
+    }
+
+    fn delete_with_usage(&self, path: &Path, inodes: &InodeTracker) -> Removal {
+        if is_symlink(path) {
+            return self.delete_symlink(path, inodes);
+        }
+
         // Calculate size before deletion (for statistics)
-        let size_before = calculate_directory_size(path).unwrap_or(0);
+        let usage = calculate_disk_usage(path, inodes).unwrap_or_default();
 
         // Perform the deletion
-        fs::remove_dir_all(path)?;
+        remove_tree(path, usage)
+    }
+
+    /// Remove a symlinked target, and what it points to only if configured to
+    fn delete_symlink(&self, link: &Path, inodes: &InodeTracker) -> Removal {
+        let mut usage = DiskUsage::default();
 
-        Ok(size_before)
+        if self.delete_symlinked {
+            let pointee = match fs::canonicalize(link) {
+                Ok(pointee) => pointee,
+                Err(e) => return Removal::Failed(NukeError::from_io(link, e)),
+            };
+            usage = calculate_disk_usage(&pointee, inodes).unwrap_or_default();
+            if let removal @ (Removal::Partial(..) | Removal::Failed(_)) = remove_tree(&pointee, usage) {
+                return removal;
+            }
+        }
+
+        match remove_symlink(link) {
+            Ok(()) => Removal::Removed(usage),
+            Err(e) if usage == DiskUsage::default() => Removal::Failed(NukeError::from_io(link, e)),
+            Err(e) => Removal::Partial(usage, NukeError::from_io(link, e)),
+        }
+    }
+
+    fn trash_with_usage(&self, path: &Path, trash: &Trash, inodes: &InodeTracker) -> Removal {
+        let usage = calculate_disk_usage(path, inodes).unwrap_or_default();
+
+        match trash.move_to_trash(path) {
+            Ok(_) => Removal::Removed(usage),
+            Err(e) => Removal::Failed(NukeError::from_anyhow(path, &e)),
+        }
     }
 }
 
+impl Removal {
+    /// Treat anything short of a full removal as an error
+    fn into_result(self) -> Result<DiskUsage, NukeError> {
+        match self {
+            Removal::Removed(usage) | Removal::Staged(usage, _) => Ok(usage),
+            Removal::Partial(_, e) => Err(e.partially_deleted()),
+            Removal::Failed(e) => Err(e),
+        }
+    }
+}
+
+/// Rename a directory tree aside for later deletion
+fn stage_with_usage(path: &Path, inodes: &InodeTracker) -> Removal {
+    let usage = calculate_disk_usage(path, inodes).unwrap_or_default();
+
+    match staging::stage(path) {
+        Ok(staged) => Removal::Staged(usage, staged),
+        Err(e) => Removal::Failed(NukeError::from_io(path, e)),
+    }
+}
+
+/// Delete a directory tree (or a single file), working out how much was freed if that fails halfway
+fn remove_tree(path: &Path, usage: DiskUsage) -> Removal {
+    let result = if path.is_file() { fs::remove_file(path) } else { fs::remove_dir_all(path) };
+    let Err(e) = result else {
+        return Removal::Removed(usage);
+    };
+
+    // remove_dir_all stops at the first error, so some of the tree may already be gone
+    let freed = calculate_disk_usage(path, &InodeTracker::new())
+        .map(|remaining| remaining.freed_since(usage))
+        .unwrap_or_default();
+    let e = NukeError::from_io(path, e);
+    if freed == DiskUsage::default() {
+        Removal::Failed(e)
+    } else {
+        Removal::Partial(freed, e)
+    }
+}
+
+fn is_symlink(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_type().is_symlink())
+}
+
+/// Remove a symlink to a directory without touching what it points to
+#[cfg(unix)]
+fn remove_symlink(link: &Path) -> std::io::Result<()> {
+    fs::remove_file(link)
+}
+
+/// Remove a symlink to a directory without touching what it points to
+#[cfg(not(unix))]
+fn remove_symlink(link: &Path) -> std::io::Result<()> {
+    fs::remove_dir(link)
+}
+
 /// Calculate the total size of a directory and its contents
 pub fn calculate_directory_size(dir: &Path) -> Result<u64> {
     let mut total_size = 0u64;
@@ -158,18 +799,106 @@ pub fn calculate_directory_size(dir: &Path) -> Result<u64> {
     Ok(total_size)
 }
 
+/// Calculate apparent and allocated size of a directory, counting hardlinked files once
+///
+/// Allocated size uses `st_blocks * 512` on Unix, so sparse files and block rounding are
+/// accounted for; other platforms fall back to file lengths. Symlinks, including a
+/// symlinked `dir` itself, are not followed.
+pub fn calculate_disk_usage(dir: &Path, inodes: &InodeTracker) -> Result<DiskUsage> {
+    let mut usage = DiskUsage::default();
+
+    for entry in walkdir::WalkDir::new(dir).follow_root_links(false) {
+        let entry = entry?;
+        let file_type = entry.file_type();
+        if !file_type.is_file() && !file_type.is_dir() {
+            continue;
+        }
+
+        let metadata = entry.metadata()?;
+        if file_type.is_file() && !inodes.first_sighting(&metadata) {
+            continue;
+        }
+
+        if file_type.is_file() {
+            usage.apparent += metadata.len();
+        }
+        usage.allocated += allocated_size(&metadata);
+    }
+
+    Ok(usage)
+}
+
+#[cfg(unix)]
+fn allocated_size(metadata: &fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.blocks() * 512
+}
+
+#[cfg(not(unix))]
+fn allocated_size(metadata: &fs::Metadata) -> u64 {
+    if metadata.is_file() { metadata.len() } else { 0 }
+}
+
 /// Print a summary of the cleanup operation
 pub fn print_cleanup_summary(stats: &CleanupStats) {
     println!("\n🧹 Cleanup Summary:");
     println!("  Directories found: {}", stats.directories_found);
     println!("  Successfully deleted: {}", stats.directories_deleted);
 
+    if stats.directories_trashed > 0 {
+        println!("  Moved to trash: {}", stats.directories_trashed);
+    }
+
+    if stats.directories_staged > 0 {
+        println!(
+            "  Deleting in the background: {} ({})",
+            stats.directories_staged,
+            format_bytes(stats.bytes_pending_deletion)
+        );
+    }
+
     if stats.directories_failed > 0 {
         println!("  Failed to delete: {}", stats.directories_failed);
     }
 
+    if stats.directories_in_use > 0 {
+        println!("  Skipped (in use): {}", stats.directories_in_use);
+    }
+
+    if stats.directories_cancelled > 0 {
+        println!("  Not started (interrupted): {}", stats.directories_cancelled);
+    }
+
     if stats.bytes_freed > 0 {
-        println!("  Space freed: {}", format_bytes(stats.bytes_freed));
+        println!(
+            "  Space freed: {} on disk ({} apparent)",
+            format_bytes(stats.bytes_freed),
+            format_bytes(stats.bytes_freed_apparent)
+        );
+    }
+
+    if stats.bytes_trashed > 0 {
+        println!(
+            "  Space moved to trash: {} on disk ({} apparent)",
+            format_bytes(stats.bytes_trashed),
+            format_bytes(stats.bytes_trashed_apparent)
+        );
+    }
+}
+
+/// List targets that were left partly removed, so they can be cleaned up by hand
+pub fn print_partially_deleted(stats: &CleanupStats) {
+    if stats.directories_partial == 0 {
+        return;
+    }
+
+    println!("\n⚠️  Partially deleted ({}):", stats.directories_partial);
+    for outcome in stats
+        .targets
+        .iter()
+        .filter(|outcome| outcome.status == TargetStatus::PartiallyDeleted)
+    {
+        println!("  {}", outcome.path.display());
     }
 }
 
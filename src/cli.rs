@@ -1,9 +1,13 @@
 //! Command-line interface and user interaction
 
 use anyhow::Result;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use colored::*;
-use dialoguer::{theme::ColorfulTheme, Confirm};
+use dialoguer::{theme::ColorfulTheme, Confirm, MultiSelect};
+use crate::artifact::ArtifactKind;
+use crate::cleaner::{SortOrder, TargetInfo};
+use crate::report::OutputFormat;
+use crate::{format_age, format_bytes};
 use std::path::{Path, PathBuf};
 
 /// A fast, multi-threaded tool to recursively delete node_modules directories
@@ -11,57 +15,327 @@ use std::path::{Path, PathBuf};
 #[command(name = "nuke-node-modules")]
 #[command(version, about, long_about = None)]
 pub struct Cli {
-    /// Directory to start scanning from (defaults to current directory)
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Directory to start scanning from (defaults to current directory; write a directory
+    /// named like a command as ./NAME)
     #[arg(value_name = "PATH")]
     pub path: Option<PathBuf>,
 
     /// Patterns to exclude from deletion (can be used multiple times)
-    #[arg(short, long = "exclude", value_name = "PATTERN")]
+    #[arg(short, long = "exclude", value_name = "PATTERN", global = true)]
     pub exclude_patterns: Vec<String>,
 
     /// Show what would be deleted without actually deleting
-    #[arg(short = 'n', long)]
+    #[arg(short = 'n', long, global = true)]
     pub dry_run: bool,
 
     /// Skip confirmation prompt
-    #[arg(short = 'y', long)]
+    #[arg(short = 'y', long, global = true)]
     pub no_confirm: bool,
 
+    /// Choose which directories to delete from an interactive list
+    #[arg(short, long, conflicts_with_all = ["no_confirm", "quiet", "dry_run"], global = true)]
+    pub interactive: bool,
+
     /// Suppress output (quiet mode)
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     pub quiet: bool,
 
     /// Number of threads to use for parallel deletion
-    #[arg(short, long, value_name = "N")]
+    #[arg(short, long, value_name = "N", global = true)]
     pub threads: Option<usize>,
 
     /// Show detailed information about each directory
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     pub verbose: bool,
+
+    /// Move directories to the trash instead of deleting them permanently (frees no space, so it
+    /// cannot be combined with --reclaim or --until-free)
+    #[arg(long, conflicts_with_all = ["reclaim", "until_free"], global = true)]
+    pub trash: bool,
+
+    /// Delete permanently even if a config file enables `trash`
+    #[arg(long, overrides_with = "trash", global = true)]
+    pub no_trash: bool,
+
+    /// Rename directories aside instantly and delete them in the background
+    #[arg(long, conflicts_with = "trash", global = true)]
+    pub fast: bool,
+
+    /// After deleting, reinstall each project with its package manager (npm ci, yarn, pnpm, bun)
+    #[arg(long, global = true)]
+    pub reinstall: bool,
+
+    /// Number of projects to reinstall at once (defaults to 1)
+    #[arg(long, value_name = "N", requires = "reinstall", global = true)]
+    pub reinstall_jobs: Option<usize>,
+
+    /// Delete trees staged by --fast (used internally by the background process)
+    #[arg(long, hide = true, num_args = 1.., value_name = "PATH")]
+    pub finish_deleting: Vec<PathBuf>,
+
+    /// Skip projects in git repositories with modified, deleted or untracked files (changes that
+    /// are only staged, with the files unchanged since, are not detected)
+    #[arg(long, global = true)]
+    pub skip_dirty: bool,
+
+    /// Include projects with uncommitted changes even if a config file enables `skip_dirty`
+    #[arg(long, overrides_with = "skip_dirty", global = true)]
+    pub no_skip_dirty: bool,
+
+    /// Leave node_modules alone while a running process (e.g. a dev server) uses them (Linux only)
+    #[arg(long, global = true)]
+    pub skip_in_use: bool,
+
+    /// Delete the node_modules of pnpm/npm/yarn workspace members independently of the workspace root
+    #[arg(long, global = true)]
+    pub ignore_workspaces: bool,
+
+    /// Descend into symlinked directories while scanning (targets must still resolve inside PATH)
+    #[arg(long, global = true)]
+    pub follow_symlinks: bool,
+
+    /// Don't scan into other filesystems (mount points) below PATH
+    #[arg(long, global = true)]
+    pub one_file_system: bool,
+
+    /// For a symlinked node_modules, delete what it points to instead of only removing the link
+    #[arg(long, global = true)]
+    pub delete_symlinked_node_modules: bool,
+
+    /// Start deleting as soon as targets are found instead of after the scan (needs --no-confirm
+    /// and --ignore-workspaces)
+    #[arg(
+        long,
+        global = true,
+        requires = "no_confirm",
+        conflicts_with_all = ["dry_run", "interactive", "top", "reclaim", "until_free"]
+    )]
+    pub pipeline: bool,
+
+    /// Only delete node_modules of projects unused for at least this long (e.g. 30d, 12h, 2w)
+    #[arg(long, value_name = "AGE", value_parser = crate::parse_age, global = true)]
+    pub older_than: Option<std::time::Duration>,
+
+    /// Output format (json and ndjson are intended for scripts)
+    #[arg(long, global = true, value_enum, value_name = "FORMAT", default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+
+    /// Artifact kinds to clean up (can be used multiple times, defaults to node-modules)
+    #[arg(long = "kind", value_enum, value_name = "KIND", global = true)]
+    pub kinds: Vec<ArtifactKind>,
+
+    /// Only delete targets taking at least this much disk space (e.g. 100M, 1.5 GB)
+    #[arg(long, value_name = "SIZE", value_parser = crate::parse_bytes, global = true)]
+    pub min_size: Option<u64>,
+
+    /// Only delete targets taking at most this much disk space (e.g. 10G)
+    #[arg(long, value_name = "SIZE", value_parser = crate::parse_bytes, global = true)]
+    pub max_size: Option<u64>,
+
+    /// Order in which targets are listed and deleted
+    #[arg(long, value_enum, value_name = "ORDER", default_value_t = SortOrder::Path, global = true)]
+    pub sort: SortOrder,
+
+    /// Only consider the first N targets after sorting (e.g. --sort size --top 10)
+    #[arg(long, value_name = "N", global = true)]
+    pub top: Option<usize>,
+
+    /// Delete just enough targets, oldest projects first, to free this much space (e.g. 20G)
+    #[arg(
+        long,
+        global = true,
+        value_name = "SIZE",
+        value_parser = crate::parse_bytes,
+        conflicts_with_all = ["top", "until_free"]
+    )]
+    pub reclaim: Option<u64>,
+
+    /// Delete targets, oldest projects first, until the filesystem has this much free space (e.g. 50G)
+    #[arg(long, value_name = "SIZE", value_parser = crate::parse_bytes, conflicts_with = "top", global = true)]
+    pub until_free: Option<u64>,
+
+    /// Allow cleaning `/`, system directories or the home directory itself
+    #[arg(long = "i-know-what-im-doing", global = true)]
+    pub i_know_what_im_doing: bool,
+
+    /// Ask a second time before deleting more than N directories
+    #[arg(long, value_name = "N", global = true)]
+    pub confirm_above_count: Option<usize>,
+
+    /// Ask a second time before deleting more than this much data (e.g. 50G)
+    #[arg(long, value_name = "SIZE", value_parser = crate::parse_bytes, global = true)]
+    pub confirm_above_size: Option<u64>,
+
+    /// Delete what the last interrupted run from PATH left behind, according to the journal
+    #[arg(long, conflicts_with_all = ["pipeline", "interactive", "no_journal"], global = true)]
+    pub resume: bool,
+
+    /// Don't record deletions in the journal ($XDG_STATE_HOME/nuke-node-modules)
+    #[arg(long, global = true)]
+    pub no_journal: bool,
+
+    /// Ignore config files and use only command-line flags
+    #[arg(long, global = true)]
+    pub no_config: bool,
+
+    /// Print the merged configuration (config files plus flags) and exit
+    #[arg(long, global = true)]
+    pub print_config: bool,
+}
+
+/// What to do; running without a command cleans up, like `clean`
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// List targets with their sizes without deleting anything
+    Scan {
+        /// Directory to start scanning from (defaults to current directory)
+        #[arg(value_name = "PATH")]
+        path: Option<PathBuf>,
+    },
+    /// Delete targets (the default when no command is given)
+    Clean {
+        /// Directory to start scanning from (defaults to current directory)
+        #[arg(value_name = "PATH")]
+        path: Option<PathBuf>,
+    },
+    /// Summarize disk usage of targets by project
+    Report {
+        /// Directory to start scanning from (defaults to current directory)
+        #[arg(value_name = "PATH")]
+        path: Option<PathBuf>,
+    },
+    /// List past runs recorded in the deletion journal
+    History {
+        /// Only show the most recent N runs
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
+    },
+    /// Move targets trashed from below PATH back to where they were
+    Restore {
+        /// Directory whose trashed targets to restore (defaults to current directory)
+        #[arg(value_name = "PATH")]
+        path: Option<PathBuf>,
+    },
+}
+
+impl Command {
+    /// The directory the command works on, if one was given
+    pub fn path(&self) -> Option<&PathBuf> {
+        match self {
+            Command::Scan { path } | Command::Clean { path } | Command::Report { path } | Command::Restore { path } => {
+                path.as_ref()
+            }
+            Command::History { .. } => None,
+        }
+    }
 }
 
 impl Cli {
-    /// Convert CLI args to Config
+    /// Convert CLI args to Config, ignoring any config files
     pub fn to_config(&self) -> crate::Config {
-        crate::Config {
-            exclude_patterns: self.exclude_patterns.clone(),
-            dry_run: self.dry_run,
-            no_confirm: self.no_confirm,
-            quiet: self.quiet,
-            threads: self.threads,
+        let mut config = crate::Config::default();
+        self.apply_to(&mut config);
+        config
+    }
+
+    /// Load config files that apply to `root_path` and override them with CLI args
+    pub fn load_config(&self, root_path: &Path) -> Result<crate::Config> {
+        let mut config = if self.no_config {
+            crate::Config::default()
+        } else {
+            crate::config_file::load_config(root_path)?
+        };
+
+        self.apply_to(&mut config);
+        Ok(config)
+    }
+
+    /// Override values in `config` with the flags given on the command line
+    ///
+    /// Exclude patterns are added to those from config files rather than replacing them, and the
+    /// `--no-*` flags turn off settings that config files turned on.
+    pub fn apply_to(&self, config: &mut crate::Config) {
+        config.exclude_patterns.extend(self.exclude_patterns.iter().cloned());
+        config.dry_run |= self.dry_run;
+        config.no_confirm |= self.no_confirm;
+        config.quiet |= self.quiet;
+        config.trash = (config.trash || self.trash) && !self.no_trash;
+        config.fast |= self.fast;
+        config.resume |= self.resume;
+        config.reinstall |= self.reinstall;
+        config.skip_dirty = (config.skip_dirty || self.skip_dirty) && !self.no_skip_dirty;
+        config.skip_in_use |= self.skip_in_use;
+        config.ignore_workspaces |= self.ignore_workspaces;
+        config.pipeline |= self.pipeline;
+        config.follow_symlinks |= self.follow_symlinks;
+        config.one_file_system |= self.one_file_system;
+        config.delete_symlinked |= self.delete_symlinked_node_modules;
+        config.interactive |= self.interactive;
+        config.safety.allow_dangerous_roots |= self.i_know_what_im_doing;
+        config.format = self.format;
+        config.sort = self.sort;
+
+        config.journal = if self.no_journal {
+            None
+        } else {
+            config.journal.take().or_else(crate::journal::default_path)
+        };
+        if self.threads.is_some() {
+            config.threads = self.threads;
+        }
+        if self.reinstall_jobs.is_some() {
+            config.reinstall_jobs = self.reinstall_jobs;
+        }
+        if self.older_than.is_some() {
+            config.older_than = self.older_than;
+        }
+        if !self.kinds.is_empty() {
+            config.kinds = self.kinds.clone();
+        }
+        if self.top.is_some() {
+            config.top = self.top;
+        }
+        if self.min_size.is_some() {
+            config.min_size = self.min_size;
+        }
+        if self.max_size.is_some() {
+            config.max_size = self.max_size;
+        }
+        if self.confirm_above_count.is_some() {
+            config.safety.confirm_above_count = self.confirm_above_count;
+        }
+        if self.confirm_above_size.is_some() {
+            config.safety.confirm_above_bytes = self.confirm_above_size;
+        }
+        if let Some(bytes) = self.reclaim {
+            config.reclaim = Some(crate::reclaim::ReclaimGoal::Bytes(bytes));
+        }
+        if let Some(bytes) = self.until_free {
+            config.reclaim = Some(crate::reclaim::ReclaimGoal::UntilFree(bytes));
         }
     }
 
-    /// Get the root path for scanning
+    /// Get the root path for scanning; a path given to the command wins
     pub fn get_root_path(&self) -> PathBuf {
-        self.path
-            .clone()
+        self.command
+            .as_ref()
+            .and_then(Command::path)
+            .or(self.path.as_ref())
+            .cloned()
             .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")))
     }
 
+    /// Whether human-readable output should be suppressed
+    pub fn is_quiet(&self) -> bool {
+        self.quiet || self.format.is_machine_readable()
+    }
+
     /// Print the banner with tool information
     pub fn print_banner(&self) {
-        if self.quiet {
+        if self.is_quiet() {
             return;
         }
 
@@ -73,27 +347,46 @@ impl Cli {
 
         if self.dry_run {
             println!("{}", "🔍 DRY RUN MODE - No files will be deleted".yellow());
+        } else if self.trash {
+            println!("{}", "🗑️  TRASH MODE - Directories will be moved to the trash".yellow());
         }
 
         println!();
     }
 
     /// Print scanning information
-    pub fn print_scan_info(&self, root_path: &Path) {
-        if self.quiet {
+    pub fn print_scan_info(&self, root_path: &Path, config: &crate::Config) {
+        if self.is_quiet() {
             return;
         }
 
         println!("📁 Scanning from: {}", root_path.display().to_string().cyan());
 
-        if !self.exclude_patterns.is_empty() {
+        if !config.exclude_patterns.is_empty() {
             println!("🚫 Exclude patterns:");
-            for pattern in &self.exclude_patterns {
+            for pattern in &config.exclude_patterns {
                 println!("  - {}", pattern.yellow());
             }
         }
 
-        if let Some(threads) = self.threads {
+        if !config.kinds.is_empty() {
+            println!("🎯 Looking for: {}", crate::artifact::describe(&config.kinds).yellow());
+        }
+
+        if let Some(age) = config.older_than {
+            println!("⏳ Only projects unused for {}", format_age(age).yellow());
+        }
+
+        match (config.min_size, config.max_size) {
+            (Some(min), Some(max)) => {
+                println!("📏 Only sizes from {} to {}", format_bytes(min).yellow(), format_bytes(max).yellow())
+            }
+            (Some(min), None) => println!("📏 Only sizes of at least {}", format_bytes(min).yellow()),
+            (None, Some(max)) => println!("📏 Only sizes of at most {}", format_bytes(max).yellow()),
+            (None, None) => {}
+        }
+
+        if let Some(threads) = config.threads {
             println!("⚡ Using {} threads", threads.to_string().green());
         } else {
             println!(
@@ -142,6 +435,56 @@ pub fn confirm_deletion(targets: &[PathBuf]) -> Result<bool> {
     Ok(confirmation)
 }
 
+/// Ask once more before a deletion that exceeds the safety thresholds
+pub fn confirm_large_deletion(reason: &str) -> Result<bool> {
+    let theme = ColorfulTheme::default();
+
+    println!("{}", format!("⚠️  This is a large deletion: {}", reason).yellow().bold());
+
+    let confirmation = Confirm::with_theme(&theme)
+        .with_prompt("Really delete all of them?")
+        .default(false)
+        .interact()?;
+
+    Ok(confirmation)
+}
+
+/// Let the user pick which targets to delete, listing the largest first
+pub fn select_targets(targets: &[TargetInfo]) -> Result<Vec<PathBuf>> {
+    let theme = ColorfulTheme::default();
+
+    let mut sorted: Vec<&TargetInfo> = targets.iter().collect();
+    sorted.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path)));
+
+    let items: Vec<String> = sorted
+        .iter()
+        .map(|info| {
+            let age = info.age.map(format_age).unwrap_or_else(|| "unknown".to_string());
+            format!(
+                "{:>10}  {:>12}  {}",
+                format_bytes(info.bytes),
+                age,
+                info.path.parent().unwrap_or(&info.path).display()
+            )
+        })
+        .collect();
+
+    println!(
+        "{}",
+        format!("Found {} directories (size, project age, path):", targets.len())
+            .bright_white()
+            .bold()
+    );
+
+    let chosen = MultiSelect::with_theme(&theme)
+        .with_prompt("Select directories to delete (space to toggle, enter to confirm)")
+        .items(&items)
+        .max_length(20)
+        .interact()?;
+
+    Ok(chosen.into_iter().map(|i| sorted[i].path.clone()).collect())
+}
+
 /// Print verbose information about directories
 pub fn print_verbose_info(targets: &[PathBuf]) -> Result<()> {
     for (i, target) in targets.iter().enumerate() {
@@ -159,6 +502,10 @@ pub fn print_verbose_info(targets: &[PathBuf]) -> Result<()> {
             }
         }
 
+        if let Some(age) = crate::scanner::project_age(target) {
+            println!("   Project age: {}", crate::format_age(age));
+        }
+
         // Try to estimate size (basic estimation)
         if let Ok(entries) = std::fs::read_dir(target) {
             let count = entries.count();
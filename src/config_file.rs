@@ -0,0 +1,211 @@
+//! Loading defaults from TOML config files
+//!
+//! Settings are merged in order of increasing precedence:
+//! built-in defaults, `~/.config/nuke-node-modules/config.toml`, the nearest
+//! `.nuke-node-modules.toml` in the scanned tree (or its ancestors), and finally CLI flags.
+//! Exclude patterns are the exception: each layer adds its patterns to those of the layers before.
+
+use crate::artifact::ArtifactKind;
+use crate::{format_bytes, parse_age, parse_bytes, Config};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// File name of the per-tree config file
+pub const TREE_CONFIG_FILE: &str = ".nuke-node-modules.toml";
+
+/// Settings that can be provided by a config file
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FileConfig {
+    /// Default exclude patterns
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    /// Number of threads to use
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threads: Option<usize>,
+    /// Number of projects to reinstall at once with `--reinstall`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reinstall_jobs: Option<usize>,
+    /// Minimum project age, e.g. `"30d"`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub older_than: Option<String>,
+    /// Minimum target size, e.g. `"100M"`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_size: Option<String>,
+    /// Maximum target size, e.g. `"10G"`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_size: Option<String>,
+    /// Move directories to the trash instead of deleting them
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trash: Option<bool>,
+    /// Skip projects in git repositories with uncommitted changes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skip_dirty: Option<bool>,
+    /// Ask a second time before deleting more than this many directories
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confirm_above_count: Option<usize>,
+    /// Ask a second time before deleting more than this much data, e.g. `"50G"`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confirm_above_size: Option<String>,
+    /// Artifact kinds to clean up, e.g. `["node-modules", "target"]`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub kinds: Vec<ArtifactKind>,
+}
+
+impl FileConfig {
+    /// Read and parse a config file
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file '{}'", path.display()))?;
+
+        toml::from_str(&contents)
+            .with_context(|| format!("Invalid config file '{}'", path.display()))
+    }
+
+    /// Overlay `other` on top of `self`; values set in `other` win, and its excludes are added
+    pub fn merge(mut self, other: FileConfig) -> Self {
+        for pattern in other.exclude {
+            if !self.exclude.contains(&pattern) {
+                self.exclude.push(pattern);
+            }
+        }
+        self.threads = other.threads.or(self.threads);
+        self.reinstall_jobs = other.reinstall_jobs.or(self.reinstall_jobs);
+        self.older_than = other.older_than.or(self.older_than);
+        self.min_size = other.min_size.or(self.min_size);
+        self.max_size = other.max_size.or(self.max_size);
+        self.trash = other.trash.or(self.trash);
+        self.skip_dirty = other.skip_dirty.or(self.skip_dirty);
+        self.confirm_above_count = other.confirm_above_count.or(self.confirm_above_count);
+        self.confirm_above_size = other.confirm_above_size.or(self.confirm_above_size);
+        if !other.kinds.is_empty() {
+            self.kinds = other.kinds;
+        }
+        self
+    }
+
+    /// Apply these settings to a `Config`
+    pub fn apply_to(&self, config: &mut Config) -> Result<()> {
+        config.exclude_patterns.extend(self.exclude.iter().cloned());
+
+        if let Some(threads) = self.threads {
+            config.threads = Some(threads);
+        }
+        if let Some(jobs) = self.reinstall_jobs {
+            config.reinstall_jobs = Some(jobs);
+        }
+        if let Some(age) = &self.older_than {
+            config.older_than = Some(parse_age(age).context("Invalid 'older_than' in config file")?);
+        }
+        if let Some(size) = &self.min_size {
+            config.min_size = Some(parse_bytes(size).context("Invalid 'min_size' in config file")?);
+        }
+        if let Some(size) = &self.max_size {
+            config.max_size = Some(parse_bytes(size).context("Invalid 'max_size' in config file")?);
+        }
+        if let Some(trash) = self.trash {
+            config.trash = trash;
+        }
+        if let Some(skip_dirty) = self.skip_dirty {
+            config.skip_dirty = skip_dirty;
+        }
+        if let Some(count) = self.confirm_above_count {
+            config.safety.confirm_above_count = Some(count);
+        }
+        if let Some(size) = &self.confirm_above_size {
+            config.safety.confirm_above_bytes =
+                Some(parse_bytes(size).context("Invalid 'confirm_above_size' in config file")?);
+        }
+        if !self.kinds.is_empty() {
+            config.kinds = self.kinds.clone();
+        }
+
+        Ok(())
+    }
+
+    /// Describe the file-configurable settings of a `Config`
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            exclude: config.exclude_patterns.clone(),
+            threads: config.threads,
+            reinstall_jobs: config.reinstall_jobs,
+            older_than: config.older_than.map(age_to_string),
+            min_size: config.min_size.map(format_bytes),
+            max_size: config.max_size.map(format_bytes),
+            trash: Some(config.trash),
+            skip_dirty: Some(config.skip_dirty),
+            confirm_above_count: config.safety.confirm_above_count,
+            confirm_above_size: config.safety.confirm_above_bytes.map(format_bytes),
+            kinds: config.kinds.clone(),
+        }
+    }
+
+    /// Render as TOML
+    pub fn to_toml(&self) -> Result<String> {
+        Ok(toml::to_string(self)?)
+    }
+}
+
+/// Location of the per-user config file
+pub fn user_config_path() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .filter(|v| !v.is_empty())
+                .map(|home| PathBuf::from(home).join(".config"))
+        })?;
+
+    Some(config_home.join("nuke-node-modules").join("config.toml"))
+}
+
+/// Find the nearest per-tree config file in `root` or any of its ancestors
+pub fn find_tree_config(root: &Path) -> Option<PathBuf> {
+    let root = std::path::absolute(root).ok()?;
+
+    root.ancestors()
+        .map(|dir| dir.join(TREE_CONFIG_FILE))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Load and merge the user and per-tree config files that apply to `root`
+pub fn load_file_config(root: &Path) -> Result<FileConfig> {
+    let mut merged = FileConfig::default();
+
+    for path in [user_config_path(), find_tree_config(root)].into_iter().flatten() {
+        if path.is_file() {
+            merged = merged.merge(FileConfig::load(&path)?);
+        }
+    }
+
+    Ok(merged)
+}
+
+/// Build a `Config` from the built-in defaults and any config files that apply to `root`
+pub fn load_config(root: &Path) -> Result<Config> {
+    let mut config = Config::default();
+    load_file_config(root)?.apply_to(&mut config)?;
+    Ok(config)
+}
+
+/// Render a duration in the largest unit that represents it exactly
+fn age_to_string(age: Duration) -> String {
+    const UNITS: &[(u64, &str)] = &[
+        (7 * 24 * 60 * 60, "w"),
+        (24 * 60 * 60, "d"),
+        (60 * 60, "h"),
+        (60, "m"),
+    ];
+
+    let secs = age.as_secs();
+    for &(unit_secs, suffix) in UNITS {
+        if secs > 0 && secs % unit_secs == 0 {
+            return format!("{}{}", secs / unit_secs, suffix);
+        }
+    }
+
+    format!("{}s", secs)
+}
@@ -0,0 +1,95 @@
+//! Typed errors for targets that could not be cleaned up
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Why a target could not be removed (or was refused), with the path concerned
+///
+/// I/O sources are shared behind an `Arc` so outcomes carrying an error can be cloned.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum NukeError {
+    /// The target, or something inside it, may not be removed by this user
+    #[error("permission denied: '{}' ({source})", path.display())]
+    PermissionDenied {
+        path: PathBuf,
+        #[source]
+        source: Arc<io::Error>,
+    },
+    /// The target is in use, e.g. by a running process or as a mount point
+    #[error("'{}' is busy: {reason}", path.display())]
+    Busy { path: PathBuf, reason: String },
+    /// Removing the target would break a safety rule
+    #[error("Safety check failed: {reason}")]
+    SafetyViolation { path: PathBuf, reason: String },
+    /// Part of the target was removed before an error stopped the removal
+    #[error("'{}' was only partially deleted: {source}", path.display())]
+    PartiallyDeleted {
+        path: PathBuf,
+        #[source]
+        source: Box<NukeError>,
+    },
+    /// The target does not exist (any more)
+    #[error("'{}' does not exist", path.display())]
+    NotFound { path: PathBuf },
+    /// Any other I/O error
+    #[error("'{}': {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: Arc<io::Error>,
+    },
+}
+
+impl NukeError {
+    /// Classify an I/O error that occurred while working on `path`
+    pub fn from_io(path: &Path, error: io::Error) -> Self {
+        let path = path.to_path_buf();
+        match error.kind() {
+            io::ErrorKind::PermissionDenied => NukeError::PermissionDenied {
+                path,
+                source: Arc::new(error),
+            },
+            io::ErrorKind::NotFound => NukeError::NotFound { path },
+            io::ErrorKind::ResourceBusy => NukeError::Busy {
+                path,
+                reason: error.to_string(),
+            },
+            _ => NukeError::Io {
+                path,
+                source: Arc::new(error),
+            },
+        }
+    }
+
+    /// Classify an error with context, by the kind of the I/O error that caused it
+    ///
+    /// The full message, context included, is kept.
+    pub fn from_anyhow(path: &Path, error: &anyhow::Error) -> Self {
+        let kind = error
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<io::Error>())
+            .map_or(io::ErrorKind::Other, io::Error::kind);
+        Self::from_io(path, io::Error::new(kind, format!("{:#}", error)))
+    }
+
+    /// Mark this error as having stopped a removal halfway
+    pub fn partially_deleted(self) -> Self {
+        NukeError::PartiallyDeleted {
+            path: self.path().to_path_buf(),
+            source: Box::new(self),
+        }
+    }
+
+    /// The path the error concerns
+    pub fn path(&self) -> &Path {
+        match self {
+            NukeError::PermissionDenied { path, .. }
+            | NukeError::Busy { path, .. }
+            | NukeError::SafetyViolation { path, .. }
+            | NukeError::PartiallyDeleted { path, .. }
+            | NukeError::NotFound { path }
+            | NukeError::Io { path, .. } => path,
+        }
+    }
+}
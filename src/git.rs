@@ -0,0 +1,370 @@
+//! Minimal, read-only git support for protecting work in progress
+//!
+//! Only local `.git` metadata is read: the repository is located by walking up from a
+//! path, and its index file is parsed (versions 2 to 4) to find tracked files.
+//! Working tree changes are detected the way `git status` does it cheaply, by comparing
+//! each tracked file's size and modification time with what the index recorded, and by
+//! looking for files that are neither tracked nor ignored. Objects are never read, so
+//! changes that are staged and otherwise unchanged since (the index differs from `HEAD`,
+//! but the working tree matches the index) are not noticed.
+
+use crate::artifact::ArtifactKind;
+use anyhow::{Context, Result};
+use ignore::WalkBuilder;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Mode of a gitlink (submodule) entry
+const MODE_GITLINK: u32 = 0o160000;
+/// Object type bits of an entry mode
+const MODE_TYPE_MASK: u32 = 0o170000;
+/// Entry flag: assume unchanged (`git update-index --assume-unchanged`)
+const FLAG_ASSUME_VALID: u16 = 0x8000;
+/// Entry flag: an extended flags field follows (version 3 and later)
+const FLAG_EXTENDED: u16 = 0x4000;
+/// Entry flag bits holding the merge stage
+const FLAG_STAGE_MASK: u16 = 0x3000;
+/// Extended entry flag: excluded by a sparse checkout
+const EXTENDED_SKIP_WORKTREE: u16 = 0x4000;
+/// Smallest possible entry apart from its object id: stat fields, flags and a NUL-terminated path
+const MIN_ENTRY_LEN: usize = 40 + 2 + 1;
+
+/// A git working tree and the directory holding its metadata
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repository {
+    work_tree: PathBuf,
+    git_dir: PathBuf,
+}
+
+impl Repository {
+    /// Find the repository whose working tree contains `path`
+    ///
+    /// Linked worktrees and submodules, whose `.git` is a file pointing elsewhere, are supported.
+    pub fn discover(path: &Path) -> Option<Self> {
+        let path = std::path::absolute(path).ok()?;
+
+        path.ancestors().find_map(|dir| {
+            let dot_git = dir.join(".git");
+            let git_dir = if dot_git.is_dir() {
+                dot_git
+            } else if dot_git.is_file() {
+                read_gitdir_file(&dot_git)?
+            } else {
+                return None;
+            };
+
+            Some(Self {
+                work_tree: dir.to_path_buf(),
+                git_dir,
+            })
+        })
+    }
+
+    /// Root of the working tree
+    pub fn work_tree(&self) -> &Path {
+        &self.work_tree
+    }
+
+    /// Directory holding this working tree's metadata (index, HEAD)
+    pub fn git_dir(&self) -> &Path {
+        &self.git_dir
+    }
+
+    /// Read and parse the index of this working tree
+    ///
+    /// A repository without an index (nothing staged yet) has an empty one.
+    pub fn read_index(&self) -> Result<Index> {
+        let path = self.git_dir.join("index");
+        match fs::read(&path) {
+            Ok(bytes) => Index::parse(&bytes, self.hash_len())
+                .with_context(|| format!("Failed to parse git index '{}'", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Index::default()),
+            Err(e) => Err(e).with_context(|| format!("Failed to read git index '{}'", path.display())),
+        }
+    }
+
+    /// Length of object ids in this repository (SHA-1 unless configured for SHA-256)
+    fn hash_len(&self) -> usize {
+        // Linked worktrees share the config of the main repository
+        let common_dir = fs::read_to_string(self.git_dir.join("commondir"))
+            .map(|dir| self.git_dir.join(dir.trim()))
+            .unwrap_or_else(|_| self.git_dir.clone());
+
+        let uses_sha256 = fs::read_to_string(common_dir.join("config")).is_ok_and(|config| {
+            config.lines().any(|line| {
+                let line: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+                line.eq_ignore_ascii_case("objectformat=sha256")
+            })
+        });
+
+        if uses_sha256 { 32 } else { 20 }
+    }
+}
+
+/// Resolve a `.git` file of the form `gitdir: <path>`
+fn read_gitdir_file(dot_git: &Path) -> Option<PathBuf> {
+    let contents = fs::read_to_string(dot_git).ok()?;
+    let target = contents.lines().next()?.strip_prefix("gitdir:")?.trim();
+
+    let git_dir = dot_git.parent()?.join(target);
+    git_dir.is_dir().then_some(git_dir)
+}
+
+/// A file recorded in the git index
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexEntry {
+    /// Path relative to the working tree, with `/` separators
+    pub path: Vec<u8>,
+    /// Modification time recorded when the file was last staged or refreshed
+    pub mtime_secs: u32,
+    /// Nanosecond part of the modification time (0 if not recorded)
+    pub mtime_nanos: u32,
+    /// File size, truncated to 32 bits
+    pub size: u32,
+    /// File mode, including the object type bits
+    pub mode: u32,
+    /// Merge stage (non-zero for unresolved conflicts)
+    pub stage: u8,
+    /// Whether the file is excluded from the working tree or marked assume-unchanged
+    pub ignore_worktree: bool,
+}
+
+/// Parsed contents of a git index file
+#[derive(Debug, Clone, Default)]
+pub struct Index {
+    entries: Vec<IndexEntry>,
+}
+
+impl Index {
+    /// Parse an index file, given the object id length of the repository
+    pub fn parse(bytes: &[u8], hash_len: usize) -> Result<Self> {
+        let mut reader = Reader { bytes, pos: 0 };
+
+        if reader.take(4)? != b"DIRC" {
+            return Err(anyhow::anyhow!("missing index signature"));
+        }
+        let version = reader.u32()?;
+        if !(2..=4).contains(&version) {
+            return Err(anyhow::anyhow!("unsupported index version {}", version));
+        }
+        let count = reader.u32()? as usize;
+
+        // The count comes from the file, so don't reserve more entries than the file can hold
+        let mut entries = Vec::with_capacity(count.min(bytes.len() / (MIN_ENTRY_LEN + hash_len)));
+        let mut previous_path: Vec<u8> = Vec::new();
+
+        for _ in 0..count {
+            let start = reader.pos;
+
+            // ctime (2), mtime (2), dev, ino, mode, uid, gid, size
+            reader.take(8)?;
+            let mtime_secs = reader.u32()?;
+            let mtime_nanos = reader.u32()?;
+            reader.take(8)?;
+            let mode = reader.u32()?;
+            reader.take(8)?;
+            let size = reader.u32()?;
+            reader.take(hash_len)?;
+
+            let flags = reader.u16()?;
+            let extended = if version >= 3 && flags & FLAG_EXTENDED != 0 {
+                reader.u16()?
+            } else {
+                0
+            };
+
+            let path = if version == 4 {
+                // Paths are stored as "drop N bytes from the previous path, then append"
+                let strip = reader.varint()?;
+                let keep = previous_path
+                    .len()
+                    .checked_sub(strip)
+                    .ok_or_else(|| anyhow::anyhow!("corrupt path compression"))?;
+                let mut path = previous_path[..keep].to_vec();
+                path.extend_from_slice(reader.until_nul()?);
+                path
+            } else {
+                let path = reader.until_nul()?.to_vec();
+                // Entries are NUL-padded to a multiple of eight bytes
+                let len = reader.pos - start;
+                reader.take((8 - len % 8) % 8)?;
+                path
+            };
+
+            previous_path.clone_from(&path);
+            entries.push(IndexEntry {
+                path,
+                mtime_secs,
+                mtime_nanos,
+                size,
+                mode,
+                stage: ((flags & FLAG_STAGE_MASK) >> 12) as u8,
+                ignore_worktree: flags & FLAG_ASSUME_VALID != 0 || extended & EXTENDED_SKIP_WORKTREE != 0,
+            });
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// All entries, in index order
+    pub fn entries(&self) -> &[IndexEntry] {
+        &self.entries
+    }
+
+    /// Whether any file at or below `relative` (relative to the working tree) is tracked
+    pub fn tracks(&self, relative: &Path) -> bool {
+        let Some(prefix) = index_path(relative) else {
+            return false;
+        };
+
+        self.entries.iter().any(|entry| {
+            entry.path.starts_with(&prefix)
+                && (entry.path.len() == prefix.len() || entry.path[prefix.len()] == b'/')
+        })
+    }
+
+    /// Whether the working tree differs from the index
+    ///
+    /// Reports unresolved conflicts, and tracked files that were deleted or whose size or
+    /// modification time no longer match the index. Untracked files are left to
+    /// [`has_untracked`](Self::has_untracked).
+    pub fn has_changes(&self, work_tree: &Path) -> bool {
+        self.entries.iter().any(|entry| {
+            if entry.stage != 0 {
+                return true;
+            }
+            if entry.ignore_worktree || entry.mode & MODE_TYPE_MASK == MODE_GITLINK {
+                return false;
+            }
+
+            let Some(path) = worktree_path(work_tree, &entry.path) else {
+                return true;
+            };
+            match fs::symlink_metadata(path) {
+                Ok(metadata) => !entry.matches_stat(&metadata),
+                Err(_) => true,
+            }
+        })
+    }
+
+    /// Whether the working tree holds a file that is neither tracked nor ignored
+    ///
+    /// Ignore rules come from `.gitignore` files, `.git/info/exclude` and the global excludes
+    /// file. Artifacts such as node_modules are never work in progress, so they don't count
+    /// even when not ignored, and nested repositories are not looked into.
+    pub fn has_untracked(&self, work_tree: &Path) -> bool {
+        let tracked: HashSet<&[u8]> = self.entries.iter().map(|entry| entry.path.as_slice()).collect();
+
+        WalkBuilder::new(work_tree)
+            .hidden(false)
+            .parents(false)
+            .filter_entry(|entry| {
+                let is_dir = entry.file_type().is_some_and(|file_type| file_type.is_dir());
+                let nested_repo = is_dir && entry.path().join(".git").exists();
+                entry.depth() == 0
+                    || (entry.file_name() != ".git" && !nested_repo && !ArtifactKind::is_artifact_path(entry.path()))
+            })
+            .build()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_some_and(|file_type| !file_type.is_dir()))
+            .any(|entry| {
+                let relative = entry.path().strip_prefix(work_tree).ok().and_then(index_path);
+                relative.is_some_and(|path| !tracked.contains(path.as_slice()))
+            })
+    }
+}
+
+impl IndexEntry {
+    /// Compare the recorded size and modification time with the file on disk
+    fn matches_stat(&self, metadata: &fs::Metadata) -> bool {
+        if metadata.len() as u32 != self.size {
+            return false;
+        }
+
+        let Some(modified) = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        else {
+            return false;
+        };
+
+        // Older git versions and some filesystems do not record nanoseconds
+        modified.as_secs() as u32 == self.mtime_secs
+            && (self.mtime_nanos == 0 || modified.subsec_nanos() == self.mtime_nanos)
+    }
+}
+
+/// Convert a relative filesystem path to the `/`-separated form used in the index
+fn index_path(relative: &Path) -> Option<Vec<u8>> {
+    let parts: Option<Vec<&str>> = relative
+        .components()
+        .map(|component| match component {
+            std::path::Component::Normal(part) => part.to_str(),
+            _ => None,
+        })
+        .collect();
+
+    Some(parts?.join("/").into_bytes())
+}
+
+/// Convert an index path back to a path inside `work_tree`
+fn worktree_path(work_tree: &Path, path: &[u8]) -> Option<PathBuf> {
+    let path = std::str::from_utf8(path).ok()?;
+    Some(path.split('/').fold(work_tree.to_path_buf(), |acc, part| acc.join(part)))
+}
+
+/// Cursor over the big-endian fields of an index file
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| anyhow::anyhow!("unexpected end of index"))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Git's offset varint: each continuation adds one before shifting
+    fn varint(&mut self) -> Result<usize> {
+        let mut byte = self.take(1)?[0];
+        let mut value = (byte & 0x7f) as usize;
+        while byte & 0x80 != 0 {
+            byte = self.take(1)?[0];
+            value = value
+                .checked_add(1)
+                .and_then(|v| v.checked_mul(128))
+                .ok_or_else(|| anyhow::anyhow!("corrupt varint"))?
+                | (byte & 0x7f) as usize;
+        }
+        Ok(value)
+    }
+
+    fn until_nul(&mut self) -> Result<&'a [u8]> {
+        let len = self.bytes[self.pos..]
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or_else(|| anyhow::anyhow!("unterminated path"))?;
+        let path = self.take(len)?;
+        self.take(1)?;
+        Ok(path)
+    }
+}
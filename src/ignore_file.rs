@@ -0,0 +1,83 @@
+//! `.nukeignore` files: gitignore-style rules for directories the scanner should never enter
+//!
+//! Each directory may contain a `.nukeignore`. Its patterns are anchored at that directory,
+//! and rules in deeper files take precedence over those closer to the scan root, so a
+//! `!negation` in a subdirectory can re-include something a parent ignored.
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
+use std::path::Path;
+use std::sync::Arc;
+
+/// File name of ignore files
+pub const IGNORE_FILE: &str = ".nukeignore";
+
+/// Ignore rules in effect for one directory: its own `.nukeignore` plus those of its ancestors
+#[derive(Debug, Default)]
+pub struct IgnoreRules {
+    parent: Option<Arc<IgnoreRules>>,
+    matcher: Option<Gitignore>,
+}
+
+impl IgnoreRules {
+    /// Rules for the scan root, from its `.nukeignore` if there is one
+    pub fn root(dir: &Path) -> Arc<Self> {
+        Arc::new(Self {
+            parent: None,
+            matcher: load(dir),
+        })
+    }
+
+    /// Rules for `dir`, a subdirectory of the directory these rules belong to
+    ///
+    /// Directories without a `.nukeignore` share their parent's rules.
+    pub fn descend(self: &Arc<Self>, dir: &Path) -> Arc<Self> {
+        match load(dir) {
+            Some(matcher) => Arc::new(Self {
+                parent: Some(Arc::clone(self)),
+                matcher: Some(matcher),
+            }),
+            None => Arc::clone(self),
+        }
+    }
+
+    /// Whether `path` is ignored; the deepest file with a matching rule decides
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let mut rules = Some(self);
+
+        while let Some(current) = rules {
+            if let Some(matcher) = &current.matcher {
+                match matcher.matched(path, is_dir) {
+                    Match::Ignore(_) => return true,
+                    Match::Whitelist(_) => return false,
+                    Match::None => {}
+                }
+            }
+            rules = current.parent.as_deref();
+        }
+
+        false
+    }
+}
+
+/// Parse `dir/.nukeignore`, warning about (and skipping) invalid lines
+fn load(dir: &Path) -> Option<Gitignore> {
+    let path = dir.join(IGNORE_FILE);
+    if !path.is_file() {
+        return None;
+    }
+
+    let mut builder = GitignoreBuilder::new(dir);
+    if let Some(e) = builder.add(&path) {
+        eprintln!("Warning: {}", e);
+    }
+
+    match builder.build() {
+        Ok(matcher) if !matcher.is_empty() => Some(matcher),
+        Ok(_) => None,
+        Err(e) => {
+            eprintln!("Warning: Invalid ignore file '{}': {}", path.display(), e);
+            None
+        }
+    }
+}
@@ -0,0 +1,113 @@
+//! Detecting targets that running processes are using
+//!
+//! On Linux, `/proc/<pid>/cwd`, `exe`, `fd/*` and `maps` show every path a process has open.
+//! Other platforms have no cheap equivalent, so nothing is ever reported as in use there.
+
+use std::path::{Path, PathBuf};
+
+/// A process holding a path open
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessInfo {
+    /// Process id
+    pub pid: u32,
+    /// Short command name (`/proc/<pid>/comm`)
+    pub name: String,
+}
+
+impl std::fmt::Display for ProcessInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (pid {})", self.name, self.pid)
+    }
+}
+
+/// Paths opened by running processes, captured once
+#[derive(Debug, Clone, Default)]
+pub struct ProcessSnapshot {
+    open_paths: Vec<(PathBuf, ProcessInfo)>,
+}
+
+impl ProcessSnapshot {
+    /// Record the paths every readable process (other than this one) is using
+    ///
+    /// Processes owned by other users are usually unreadable and are silently left out.
+    #[cfg(target_os = "linux")]
+    pub fn capture() -> Self {
+        let mut open_paths = Vec::new();
+        let Ok(entries) = std::fs::read_dir("/proc") else {
+            return Self::default();
+        };
+
+        for entry in entries.flatten() {
+            let Some(pid) = entry.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+                continue;
+            };
+            if pid == std::process::id() {
+                continue;
+            }
+
+            let dir = entry.path();
+            let paths = process_paths(&dir);
+            if paths.is_empty() {
+                continue;
+            }
+
+            let name = std::fs::read_to_string(dir.join("comm"))
+                .map(|comm| comm.trim_end().to_string())
+                .unwrap_or_default();
+            let info = ProcessInfo { pid, name };
+            open_paths.extend(paths.into_iter().map(|path| (path, info.clone())));
+        }
+
+        Self { open_paths }
+    }
+
+    /// Record the paths every process is using (not supported on this platform)
+    #[cfg(not(target_os = "linux"))]
+    pub fn capture() -> Self {
+        Self::default()
+    }
+
+    /// A process using `target` or anything below it, if there is one
+    pub fn user_of(&self, target: &Path) -> Option<&ProcessInfo> {
+        let target = std::fs::canonicalize(target).unwrap_or_else(|_| target.to_path_buf());
+
+        self.open_paths
+            .iter()
+            .find(|(path, _)| path.starts_with(&target))
+            .map(|(_, info)| info)
+    }
+}
+
+/// Working directory, executable, open files and mapped files of one process
+#[cfg(target_os = "linux")]
+fn process_paths(dir: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = ["cwd", "exe"]
+        .iter()
+        .filter_map(|link| std::fs::read_link(dir.join(link)).ok())
+        .collect();
+
+    if let Ok(fds) = std::fs::read_dir(dir.join("fd")) {
+        paths.extend(fds.flatten().filter_map(|fd| std::fs::read_link(fd.path()).ok()));
+    }
+
+    // A mapping's path is its last column and may contain spaces; no earlier column has a `/`
+    if let Ok(maps) = std::fs::read_to_string(dir.join("maps")) {
+        paths.extend(
+            maps.lines()
+                .filter_map(|line| line.find('/').map(|start| PathBuf::from(&line[start..])))
+        );
+    }
+
+    // Sockets, pipes and the like aren't paths; deleted files keep a suffix
+    let mut paths: Vec<PathBuf> = paths
+        .into_iter()
+        .filter(|path| path.is_absolute())
+        .map(|path| match path.to_str().and_then(|s| s.strip_suffix(" (deleted)")) {
+            Some(stripped) => PathBuf::from(stripped),
+            None => path,
+        })
+        .collect();
+    paths.sort();
+    paths.dedup();
+    paths
+}
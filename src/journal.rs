@@ -0,0 +1,304 @@
+//! Append-only journal of what each run removed, for auditing and resuming interrupted runs
+//!
+//! The journal is an NDJSON file, `$XDG_STATE_HOME/nuke-node-modules/journal.ndjson` by
+//! default. Before a run deletes anything, every target is written with no outcome; once a
+//! target is dealt with, a second entry records what happened. Targets whose last entry has
+//! no outcome (or a cancelled one) were never finished.
+
+use crate::report::{TargetOutcome, TargetStatus};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// One line of the journal
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Identifier shared by all entries of one run
+    pub run: String,
+    /// When the entry was written (RFC 3339)
+    pub timestamp: String,
+    /// Root the run was started from
+    pub root: PathBuf,
+    /// Target directory
+    pub target: PathBuf,
+    /// Disk space freed in bytes, if known
+    pub bytes: Option<u64>,
+    /// What happened to the target; `None` while its deletion is pending
+    pub outcome: Option<TargetStatus>,
+    /// Error message when the deletion failed
+    pub error: Option<String>,
+}
+
+impl JournalEntry {
+    /// Whether this target still has to be dealt with
+    pub fn is_unfinished(&self) -> bool {
+        matches!(self.outcome, None | Some(TargetStatus::Cancelled))
+    }
+}
+
+/// Totals of one run, as listed by `history`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct RunSummary {
+    /// Identifier of the run
+    pub run: String,
+    /// Timestamp of the run's first entry
+    pub started: String,
+    /// Root the run was started from
+    pub root: PathBuf,
+    /// Number of targets the run set out to remove
+    pub targets: usize,
+    /// Number of targets deleted
+    pub deleted: usize,
+    /// Number of targets moved to the trash
+    pub trashed: usize,
+    /// Number of targets renamed aside for deletion in the background (fast mode)
+    pub staged: usize,
+    /// Number of targets that failed, including partially deleted ones
+    pub failed: usize,
+    /// Number of targets never finished (interrupted or crashed)
+    pub unfinished: usize,
+    /// Disk space freed in bytes
+    pub bytes_freed: u64,
+    /// Disk space moved to the trash in bytes, not freed until the trash is emptied
+    pub bytes_trashed: u64,
+}
+
+/// An open journal that entries of one run are appended to
+#[derive(Debug)]
+pub struct Journal {
+    file: Mutex<File>,
+    path: PathBuf,
+    run: String,
+    root: PathBuf,
+}
+
+impl Journal {
+    /// Open (creating if needed) the journal at `path` for a new run from `root`
+    pub fn open(path: &Path, root: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create journal directory '{}'", parent.display()))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open journal '{}'", path.display()))?;
+
+        // Runs opened by one process within the same millisecond still get distinct ids
+        static RUNS_OPENED: AtomicUsize = AtomicUsize::new(0);
+        let run = format!(
+            "{}-{}-{}",
+            chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ"),
+            std::process::id(),
+            RUNS_OPENED.fetch_add(1, Ordering::Relaxed)
+        );
+        Ok(Self {
+            file: Mutex::new(file),
+            path: path.to_path_buf(),
+            run,
+            root: std::path::absolute(root).unwrap_or_else(|_| root.to_path_buf()),
+        })
+    }
+
+    /// Identifier of the run this journal records
+    pub fn run(&self) -> &str {
+        &self.run
+    }
+
+    /// Record that these targets are about to be removed
+    pub fn record_pending(&self, targets: &[PathBuf]) {
+        for target in targets {
+            self.append(target, None, None, None);
+        }
+    }
+
+    /// Record what happened to a target
+    pub fn record_outcome(&self, outcome: &TargetOutcome) {
+        self.append(&outcome.path, outcome.bytes, Some(outcome.status), outcome.error.as_ref().map(ToString::to_string));
+    }
+
+    fn append(&self, target: &Path, bytes: Option<u64>, outcome: Option<TargetStatus>, error: Option<String>) {
+        let entry = JournalEntry {
+            run: self.run.clone(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            root: self.root.clone(),
+            target: std::path::absolute(target).unwrap_or_else(|_| target.to_path_buf()),
+            bytes,
+            outcome,
+            error,
+        };
+
+        // One write per line, so appends from concurrent runs don't interleave
+        let result = serde_json::to_string(&entry)
+            .map_err(std::io::Error::from)
+            .and_then(|line| match self.file.lock() {
+                Ok(mut file) => file.write_all(format!("{}\n", line).as_bytes()),
+                Err(_) => Ok(()),
+            });
+        if let Err(e) = result {
+            eprintln!("Warning: Failed to write journal '{}': {}", self.path.display(), e);
+        }
+    }
+}
+
+/// Location of the journal (`$XDG_STATE_HOME/nuke-node-modules`, falling back to `~/.local/state`)
+pub fn default_path() -> Option<PathBuf> {
+    let state_home = std::env::var_os("XDG_STATE_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .filter(|v| !v.is_empty())
+                .map(|home| PathBuf::from(home).join(".local/state"))
+        })?;
+
+    Some(state_home.join("nuke-node-modules").join("journal.ndjson"))
+}
+
+/// Read every entry of a journal; a missing journal has none
+///
+/// A truncated last line (from a crash mid-write) is skipped.
+pub fn read_entries(path: &Path) -> Result<Vec<JournalEntry>> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read journal '{}'", path.display())),
+    };
+
+    Ok(contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Entries of one run, reduced to the latest entry per target
+struct RunEntries<'a> {
+    run: String,
+    started: String,
+    latest: Vec<&'a JournalEntry>,
+}
+
+/// Latest entry per target of each run, in run order
+fn latest_by_run(entries: &[JournalEntry]) -> Vec<RunEntries<'_>> {
+    let mut runs: Vec<RunEntries<'_>> = Vec::new();
+    // Position of each run in `runs`, and of each of its targets in the run's list
+    let mut index: HashMap<&str, (usize, HashMap<&Path, usize>)> = HashMap::new();
+
+    for entry in entries {
+        let (position, targets) = index.entry(&entry.run).or_insert_with(|| {
+            runs.push(RunEntries {
+                run: entry.run.clone(),
+                started: entry.timestamp.clone(),
+                latest: Vec::new(),
+            });
+            (runs.len() - 1, HashMap::new())
+        });
+
+        let latest = &mut runs[*position].latest;
+        match targets.get(entry.target.as_path()) {
+            Some(&i) => latest[i] = entry,
+            None => {
+                targets.insert(&entry.target, latest.len());
+                latest.push(entry);
+            }
+        }
+    }
+
+    runs
+}
+
+/// Summarize each run in the journal, oldest first
+pub fn summarize_runs(entries: &[JournalEntry]) -> Vec<RunSummary> {
+    latest_by_run(entries)
+        .into_iter()
+        .map(|RunEntries { run, started, latest }| {
+            let mut summary = RunSummary {
+                run,
+                started,
+                root: latest.first().map(|e| e.root.clone()).unwrap_or_default(),
+                targets: latest.len(),
+                ..Default::default()
+            };
+
+            for entry in latest {
+                let bytes = entry.bytes.unwrap_or(0);
+                match entry.outcome {
+                    Some(TargetStatus::Deleted) => summary.deleted += 1,
+                    Some(TargetStatus::Trashed) => {
+                        summary.trashed += 1;
+                        summary.bytes_trashed += bytes;
+                        continue;
+                    }
+                    // Freed by the background deletion, which the journal does not follow
+                    Some(TargetStatus::Staged) => {
+                        summary.staged += 1;
+                        continue;
+                    }
+                    Some(TargetStatus::Failed | TargetStatus::PartiallyDeleted) => summary.failed += 1,
+                    _ if entry.is_unfinished() => summary.unfinished += 1,
+                    _ => {}
+                }
+                summary.bytes_freed += bytes;
+            }
+            summary
+        })
+        .collect()
+}
+
+/// Targets left unfinished by the most recent run from `root`, with that run's identifier
+///
+/// Returns `None` if the latest run from `root` finished every target.
+pub fn unfinished_targets(entries: &[JournalEntry], root: &Path) -> Option<(String, Vec<PathBuf>)> {
+    let root = std::path::absolute(root).unwrap_or_else(|_| root.to_path_buf());
+
+    latest_by_run(entries)
+        .into_iter()
+        .rev()
+        .find(|run| run.latest.first().is_some_and(|e| e.root == root))
+        .map(|RunEntries { run, latest, .. }| {
+            let targets = latest
+                .into_iter()
+                .filter(|e| e.is_unfinished())
+                .map(|e| e.target.clone())
+                .collect::<Vec<_>>();
+            (run, targets)
+        })
+        .filter(|(_, targets)| !targets.is_empty())
+}
+
+/// Print a table of past runs
+pub fn print_history(summaries: &[RunSummary]) {
+    if summaries.is_empty() {
+        println!("No runs recorded yet.");
+        return;
+    }
+
+    for summary in summaries {
+        let removed = summary.deleted + summary.trashed + summary.staged;
+        print!(
+            "{}  {}  removed {}/{}  freed {:>10}",
+            summary.started,
+            summary.root.display(),
+            removed,
+            summary.targets,
+            crate::format_bytes(summary.bytes_freed)
+        );
+        if summary.bytes_trashed > 0 {
+            print!("  trashed {}", crate::format_bytes(summary.bytes_trashed));
+        }
+        if summary.failed > 0 {
+            print!("  failed {}", summary.failed);
+        }
+        if summary.unfinished > 0 {
+            print!("  unfinished {} (resume with --resume)", summary.unfinished);
+        }
+        println!();
+    }
+}
@@ -3,8 +3,32 @@
 pub mod scanner;
 pub mod cleaner;
 pub mod cli;
+pub mod trash;
+pub mod report;
+pub mod config_file;
+pub mod artifact;
+pub mod reclaim;
+pub mod git;
+pub mod ignore_file;
+pub mod safety;
+pub mod in_use;
+pub mod staging;
+pub mod journal;
+pub mod reinstall;
+pub mod workspace;
+pub mod error;
 
 use anyhow::Result;
+use artifact::ArtifactKind;
+pub use error::NukeError;
+use cleaner::{SortOrder, TargetInfo};
+use report::{OutputFormat, ProjectUsage, TargetOutcome, TargetStatus};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
 /// Configuration for the cleanup operation
 #[derive(Debug, Clone, Default)]
@@ -19,20 +43,138 @@ pub struct Config {
     pub quiet: bool,
     /// Number of threads to use (None = auto-detect)
     pub threads: Option<usize>,
+    /// Move directories to the trash instead of deleting them permanently
+    pub trash: bool,
+    /// Only target projects that have not been used for at least this long
+    pub older_than: Option<Duration>,
+    /// Format used to print results
+    pub format: OutputFormat,
+    /// Let the user pick targets from an interactive list instead of confirming all
+    pub interactive: bool,
+    /// Skip targets smaller than this many bytes
+    pub min_size: Option<u64>,
+    /// Skip targets larger than this many bytes
+    pub max_size: Option<u64>,
+    /// Artifact kinds to look for (empty = node_modules only)
+    pub kinds: Vec<ArtifactKind>,
+    /// Order in which targets are listed and deleted
+    pub sort: SortOrder,
+    /// Only consider the first N targets after sorting
+    pub top: Option<usize>,
+    /// Delete only enough targets, oldest first, to reach this free-space goal
+    pub reclaim: Option<reclaim::ReclaimGoal>,
+    /// Skip targets in git repositories with uncommitted changes
+    pub skip_dirty: bool,
+    /// Start deleting targets while the scan is still running (requires `no_confirm`)
+    pub pipeline: bool,
+    /// Descend into symlinked directories while scanning
+    pub follow_symlinks: bool,
+    /// Don't scan across filesystem boundaries
+    pub one_file_system: bool,
+    /// Delete what a symlinked target points to instead of just removing the link
+    pub delete_symlinked: bool,
+    /// Rename targets aside and leave deleting them to the caller (see `CleanupStats::pending_deletion`)
+    pub fast: bool,
+    /// Append-only journal recording every deletion (None = no journal)
+    pub journal: Option<PathBuf>,
+    /// Finish the targets the last interrupted run from this root left behind, instead of scanning
+    pub resume: bool,
+    /// Run the project's clean-install command after deleting its node_modules
+    pub reinstall: bool,
+    /// Number of installs to run at once when reinstalling (None = one at a time)
+    pub reinstall_jobs: Option<usize>,
+    /// Treat the node_modules of workspace members independently instead of as one unit
+    pub ignore_workspaces: bool,
+    /// Leave targets alone while a running process has files open inside them
+    pub skip_in_use: bool,
+    /// Dangerous-root guards and second-confirmation thresholds
+    pub safety: safety::SafetyPolicy,
+    /// Set from another thread (e.g. a Ctrl-C handler) to stop starting new deletions
+    ///
+    /// When set during the scan, nothing is listed, confirmed or deleted once it finishes.
+    pub cancel_flag: Option<Arc<AtomicBool>>,
 }
 
+/// Why a target falls outside the size limits of a [`Config`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeRejection {
+    /// Smaller than `min_size`
+    TooSmall,
+    /// Larger than `max_size`
+    TooLarge,
+}
+
+impl Config {
+    /// Check a target's size (in bytes on disk) against `min_size` and `max_size`
+    pub fn size_rejection(&self, bytes: u64) -> Option<SizeRejection> {
+        if self.min_size.is_some_and(|min| bytes < min) {
+            Some(SizeRejection::TooSmall)
+        } else if self.max_size.is_some_and(|max| bytes > max) {
+            Some(SizeRejection::TooLarge)
+        } else {
+            None
+        }
+    }
+}
 
 /// Statistics about the cleanup operation
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize)]
 pub struct CleanupStats {
     /// Number of directories found
     pub directories_found: usize,
+    /// Number of found directories picked for deletion, e.g. by `--top` or a reclaim goal
+    pub directories_selected: usize,
     /// Number of directories successfully deleted
     pub directories_deleted: usize,
+    /// Number of directories successfully moved to the trash
+    pub directories_trashed: usize,
+    /// Number of directories renamed aside in fast mode, whose deletion is still running
+    /// (see `pending_deletion`)
+    pub directories_staged: usize,
     /// Number of directories skipped due to errors
     pub directories_failed: usize,
-    /// Total size freed (in bytes)
+    /// Number of failed directories that were left partly removed (included in `directories_failed`)
+    pub directories_partial: usize,
+    /// Number of directories skipped because a running process was using them
+    pub directories_in_use: usize,
+    /// Number of directories not attempted because the run was interrupted
+    pub directories_cancelled: usize,
+    /// Total disk space freed (allocated blocks, hardlinks counted once, in bytes)
     pub bytes_freed: u64,
+    /// Total apparent size freed (sum of file lengths, in bytes)
+    pub bytes_freed_apparent: u64,
+    /// Disk space of the targets moved to the trash, which stays in use until the trash is emptied
+    pub bytes_trashed: u64,
+    /// Apparent size of the targets moved to the trash (in bytes)
+    pub bytes_trashed_apparent: u64,
+    /// Disk space of the targets staged in fast mode, freed once `pending_deletion` is deleted
+    pub bytes_pending_deletion: u64,
+    /// Disk space of the targets measured before deletion (in bytes, 0 if not measured)
+    pub bytes_reclaimable: u64,
+    /// Apparent size of the targets measured before deletion (in bytes, 0 if not measured)
+    pub bytes_reclaimable_apparent: u64,
+    /// Number of directories left alone because they were smaller than `min_size`
+    pub directories_too_small: usize,
+    /// Number of directories left alone because they were larger than `max_size`
+    pub directories_too_large: usize,
+    /// Number of trees left staged by an earlier fast-mode run that were deleted
+    pub leftovers_removed: usize,
+    /// Per-target outcomes, sorted by path; failed ones carry a [`NukeError`]
+    #[serde(skip)]
+    pub targets: Vec<TargetOutcome>,
+    /// Trees renamed aside in fast mode that still have to be deleted
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pending_deletion: Vec<PathBuf>,
+    /// Per-project results of reinstalling dependencies, sorted by project
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub reinstalls: Vec<reinstall::ReinstallOutcome>,
+}
+
+impl CleanupStats {
+    /// Errors of the targets that could not be removed, in path order
+    pub fn errors(&self) -> impl Iterator<Item = &NukeError> {
+        self.targets.iter().filter_map(|outcome| outcome.error.as_ref())
+    }
 }
 
 /// Main entry point for the cleanup operation
@@ -40,47 +182,759 @@ pub fn cleanup_node_modules<P: AsRef<std::path::Path>>(
     root_path: P,
     config: &Config,
 ) -> Result<CleanupStats> {
-    let scanner = scanner::Scanner::new(root_path, &config.exclude_patterns);
-    let targets = scanner.find_node_modules_dirs()?;
+    let root_path = root_path.as_ref();
+
+    if config.format.is_machine_readable() && !config.dry_run && !config.no_confirm {
+        return Err(anyhow::anyhow!(
+            "Machine-readable output cannot prompt for confirmation: pass --no-confirm or --dry-run"
+        ));
+    }
+
+    if let (Some(min), Some(max)) = (config.min_size, config.max_size)
+        && min > max
+    {
+        return Err(anyhow::anyhow!(
+            "The minimum size ({}) is larger than the maximum size ({})",
+            format_bytes(min),
+            format_bytes(max)
+        ));
+    }
+
+    // Trashed directories still take up space, so they would never count towards the goal
+    if config.trash && config.reclaim.is_some() {
+        return Err(anyhow::anyhow!(
+            "Trashing frees no disk space, so it cannot be combined with --reclaim or --until-free (pass --no-trash if a config file enables trash)"
+        ));
+    }
+
+    // Dry runs never delete anything, so they may inspect any tree
+    if !config.dry_run {
+        config.safety.check_root(root_path)?;
+    }
+
+    // Machine-readable formats own stdout, so suppress the human-readable output
+    let quiet = config.quiet || config.format.is_machine_readable();
+    let noun = artifact::describe(&config.kinds);
+
+    let trash = if config.trash && !config.dry_run {
+        Some(trash::Trash::home()?)
+    } else {
+        None
+    };
+
+    let journal = match &config.journal {
+        Some(path) if !config.dry_run => Some(Arc::new(journal::Journal::open(path, root_path)?)),
+        _ => None,
+    };
+
+    let cleaner = cleaner::Cleaner::new(config.threads, !quiet)
+        .with_trash(trash)
+        .with_output(config.format)
+        .with_cancel_flag(config.cancel_flag.clone())
+        .with_root(Some(root_path.to_path_buf()))
+        .with_delete_symlinked(config.delete_symlinked)
+        .with_skip_in_use(config.skip_in_use)
+        .with_fast(config.fast)
+        .with_journal(journal);
+
+    let mut stats = if config.resume {
+        resume_run(root_path, &cleaner, config, quiet)?
+    } else {
+        let scanner = build_scanner(root_path, config);
+
+        if config.pipeline {
+            run_pipeline(scanner, &cleaner, config, quiet, &noun)?
+        } else {
+            let targets = scanner.find_node_modules_dirs_parallel(cleaner.thread_pool())?;
+            if is_cancelled(config) {
+                return interrupted_before_deleting(targets.into_iter().map(TargetOutcome::cancelled).collect(), config);
+            }
+            let leftovers_removed = if config.dry_run { 0 } else { finish_leftovers(&scanner, &cleaner, quiet) };
+
+            let mut stats = clean_targets(targets, root_path, &cleaner, config, quiet, &noun)?;
+            stats.leftovers_removed = leftovers_removed;
+            stats
+        }
+    };
 
+    if config.reinstall && !config.dry_run {
+        stats.reinstalls = reinstall_projects(&stats, config, quiet);
+    }
+    Ok(stats)
+}
+
+/// Run the clean-install command in every project whose node_modules was removed
+fn reinstall_projects(stats: &CleanupStats, config: &Config, quiet: bool) -> Vec<reinstall::ReinstallOutcome> {
+    let removed: Vec<PathBuf> = stats
+        .targets
+        .iter()
+        .filter(|outcome| matches!(outcome.status, TargetStatus::Deleted | TargetStatus::Trashed | TargetStatus::Staged))
+        .map(|outcome| outcome.path.clone())
+        .collect();
+    let projects = reinstall::projects_of(&removed);
+    if projects.is_empty() {
+        return Vec::new();
+    }
+
+    if !quiet {
+        println!("Reinstalling dependencies in {} projects...", projects.len());
+    }
+    reinstall::Reinstaller::new(config.reinstall_jobs, !quiet)
+        .with_cancel_flag(config.cancel_flag.clone())
+        .reinstall(&projects)
+}
+
+/// Measure, filter, list and confirm the targets found by a scan, then delete them
+fn clean_targets(
+    targets: Vec<PathBuf>,
+    root_path: &std::path::Path,
+    cleaner: &cleaner::Cleaner,
+    config: &Config,
+    quiet: bool,
+    noun: &str,
+) -> Result<CleanupStats> {
     if targets.is_empty() {
-        if !config.quiet {
-            println!("No node_modules directories found.");
+        if !quiet {
+            println!("No {} directories found.", noun);
         }
         return Ok(CleanupStats::default());
     }
 
-    if !config.quiet {
-        println!("Found {} node_modules directories", targets.len());
+    // Measuring walks every target, so only do it when something needs sizes or ages
+    let measured = config.dry_run
+        || config.interactive
+        || config.sort != SortOrder::Path
+        || config.top.is_some()
+        || config.reclaim.is_some()
+        || config.min_size.is_some()
+        || config.max_size.is_some()
+        || (!config.no_confirm && config.safety.confirm_above_bytes.is_some());
+
+    let (infos, filtered) = measure_and_filter(targets, cleaner, config, measured);
+
+    if !quiet {
+        if filtered.too_small > 0 {
+            println!(
+                "Skipped {} {} directories smaller than {} (--min-size)",
+                filtered.too_small,
+                noun,
+                format_bytes(config.min_size.unwrap_or_default())
+            );
+        }
+        if filtered.too_large > 0 {
+            println!(
+                "Skipped {} {} directories larger than {} (--max-size)",
+                filtered.too_large,
+                noun,
+                format_bytes(config.max_size.unwrap_or_default())
+            );
+        }
+    }
+
+    let mut stats = if infos.is_empty() {
+        if !quiet {
+            println!("No {} directories within the size limits.", noun);
+        }
+        CleanupStats::default()
+    } else {
+        clean_measured(infos, measured, root_path, cleaner, config, quiet, noun)?
+    };
+    stats.directories_too_small = filtered.too_small;
+    stats.directories_too_large = filtered.too_large;
+    Ok(stats)
+}
+
+/// List, confirm and delete targets that passed the size limits
+fn clean_measured(
+    mut infos: Vec<TargetInfo>,
+    measured: bool,
+    root_path: &std::path::Path,
+    cleaner: &cleaner::Cleaner,
+    config: &Config,
+    quiet: bool,
+    noun: &str,
+) -> Result<CleanupStats> {
+    let total_found = infos.len();
+
+    // Workspaces are sorted, counted by --top and picked for a reclaim goal as one unit, so
+    // those the filters already split can never be deleted whole
+    if !config.ignore_workspaces {
+        skip_partial_workspaces(&mut infos, quiet);
+    }
+    let units = Units::group(infos, !config.ignore_workspaces);
+    let mut selected = units.representatives.clone();
+
+    cleaner::sort_targets(&mut selected, config.sort);
+    if let Some(top) = config.top {
+        selected.truncate(top);
+    }
+
+    let mut needed = None;
+    if let Some(goal) = config.reclaim {
+        let bytes = goal.bytes_needed(root_path)?;
+        if bytes == 0 {
+            if !quiet {
+                println!("Free-space goal already met, nothing to delete.");
+            }
+            return Ok(CleanupStats {
+                directories_found: total_found,
+                ..Default::default()
+            });
+        }
+
+        selected = reclaim::select_for_reclaim(selected, bytes);
+        needed = Some(bytes);
+    }
+
+    let infos = units.expand(selected);
+
+    // Reported only once whole workspaces are in, as they can take the total past the goal
+    if let Some(needed) = needed
+        && !quiet
+    {
+        let selected_bytes: u64 = infos.iter().map(|info| info.bytes).sum();
+        println!(
+            "Selected {} directories (oldest first) to reclaim {} of the {} needed",
+            infos.len(),
+            format_bytes(selected_bytes),
+            format_bytes(needed)
+        );
+        if selected_bytes < needed {
+            println!(
+                "Warning: the goal cannot be met - every candidate left after filtering and skipping frees only {}",
+                format_bytes(selected_bytes)
+            );
+        }
+    }
+
+    if infos.is_empty() {
+        return Ok(CleanupStats {
+            directories_found: total_found,
+            ..Default::default()
+        });
+    }
+
+    let bytes_reclaimable: u64 = infos.iter().map(|info| info.bytes).sum();
+    let bytes_reclaimable_apparent: u64 = infos.iter().map(|info| info.apparent_bytes).sum();
+
+    // Interactive mode lists the targets itself, with sizes
+    if !quiet && !config.interactive {
+        println!("Found {} {} directories", total_found, noun);
+        if infos.len() < total_found && config.reclaim.is_none() {
+            println!("Showing the top {} by {}", infos.len(), config.sort);
+        }
         if config.dry_run {
             println!("DRY RUN - would delete:");
         }
-        for target in &targets {
-            println!("  {}", target.parent().unwrap_or(target).display());
+        for info in &infos {
+            let parent = info.path.parent().unwrap_or(&info.path);
+            if measured {
+                println!("  {:>10}  {}", format_bytes(info.bytes), parent.display());
+            } else {
+                println!("  {}", parent.display());
+            }
+        }
+        if measured {
+            println!(
+                "Total: {} on disk ({} apparent)",
+                format_bytes(bytes_reclaimable),
+                format_bytes(bytes_reclaimable_apparent)
+            );
         }
         println!();
     }
 
+    // Measuring can take a while; don't go on to prompt or delete if it was interrupted
+    if is_cancelled(config) {
+        let outcomes = infos
+            .iter()
+            .map(|info| TargetOutcome {
+                status: TargetStatus::Cancelled,
+                ..TargetOutcome::would_delete(info)
+            })
+            .collect();
+        return interrupted_before_deleting(outcomes, config);
+    }
+
     if config.dry_run {
+        let outcomes: Vec<TargetOutcome> = infos.iter().map(TargetOutcome::would_delete).collect();
+
+        if config.format == OutputFormat::Ndjson {
+            for outcome in &outcomes {
+                report::emit_target(outcome)?;
+            }
+        }
+
         return Ok(CleanupStats {
-            directories_found: targets.len(),
+            directories_found: total_found,
+            directories_selected: infos.len(),
+            bytes_reclaimable,
+            bytes_reclaimable_apparent,
+            targets: outcomes,
             ..Default::default()
         });
     }
 
-    if !config.no_confirm && !config.quiet
-        && !cli::confirm_deletion(&targets)? {
-        if !config.quiet {
+    let listed = infos.len();
+    let aborted = || {
+        if !quiet {
+            println!("Aborted");
+        }
+        Ok(CleanupStats {
+            directories_found: total_found,
+            directories_selected: listed,
+            bytes_reclaimable,
+            bytes_reclaimable_apparent,
+            ..Default::default()
+        })
+    };
+
+    let targets = if config.interactive {
+        let selected = cli::select_targets(&infos)?;
+        if selected.is_empty() {
+            if !quiet {
+                println!("Nothing selected");
+            }
+            return Ok(CleanupStats {
+                directories_found: total_found,
+                bytes_reclaimable,
+                bytes_reclaimable_apparent,
+                ..Default::default()
+            });
+        }
+        if config.ignore_workspaces {
+            selected
+        } else {
+            complete_workspaces(selected, &infos, quiet)
+        }
+    } else {
+        let targets: Vec<PathBuf> = infos.iter().map(|info| info.path.clone()).collect();
+        if !config.no_confirm && !quiet
+            && !cli::confirm_deletion(&targets)? {
+            return aborted();
+        }
+        targets
+    };
+
+    // Large deletions are confirmed a second time, after the list has been seen
+    if !config.no_confirm && !quiet {
+        let chosen: std::collections::HashSet<&PathBuf> = targets.iter().collect();
+        let bytes = infos
+            .iter()
+            .filter(|info| chosen.contains(&info.path))
+            .map(|info| info.bytes)
+            .sum();
+        if let Some(reason) = config.safety.second_confirmation_reason(targets.len(), bytes)
+            && !cli::confirm_large_deletion(&reason)?
+        {
+            return aborted();
+        }
+    }
+
+    let mut stats = cleaner.delete_directories(targets)?;
+    stats.directories_found = total_found;
+    stats.bytes_reclaimable = bytes_reclaimable;
+    stats.bytes_reclaimable_apparent = bytes_reclaimable_apparent;
+
+    Ok(stats)
+}
+
+/// Whether the run was interrupted, e.g. by Ctrl-C
+fn is_cancelled(config: &Config) -> bool {
+    config.cancel_flag.as_ref().is_some_and(|flag| flag.load(Ordering::SeqCst))
+}
+
+/// Stats for a run interrupted before any deletion started, leaving every target untouched
+fn interrupted_before_deleting(outcomes: Vec<TargetOutcome>, config: &Config) -> Result<CleanupStats> {
+    if config.format == OutputFormat::Ndjson {
+        for outcome in &outcomes {
+            report::emit_target(outcome)?;
+        }
+    }
+
+    Ok(CleanupStats {
+        directories_found: outcomes.len(),
+        directories_selected: outcomes.len(),
+        directories_cancelled: outcomes.len(),
+        targets: outcomes,
+        ..Default::default()
+    })
+}
+
+/// Targets grouped so that the node_modules of each workspace form a single unit
+///
+/// A unit is represented by a [`TargetInfo`] under the path of its first member, with the
+/// members' sizes summed and the age of the most recently used one. Targets outside any
+/// workspace, or all targets when not grouping, are units on their own.
+struct Units {
+    representatives: Vec<TargetInfo>,
+    members: HashMap<PathBuf, Vec<TargetInfo>>,
+}
+
+impl Units {
+    fn group(mut infos: Vec<TargetInfo>, by_workspace: bool) -> Self {
+        infos.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let mut index = workspace::WorkspaceIndex::new();
+        let mut keys: HashMap<PathBuf, PathBuf> = HashMap::new();
+        let mut members: HashMap<PathBuf, Vec<TargetInfo>> = HashMap::new();
+        for info in infos {
+            let workspace = (by_workspace && info.path.file_name().is_some_and(|name| name == "node_modules"))
+                .then(|| index.workspace_of(artifact::project_of(&info.path)).map(|w| w.root.clone()))
+                .flatten();
+            // Members are sorted, so the first one names the unit
+            let key = match workspace {
+                Some(root) => keys.entry(root).or_insert_with(|| info.path.clone()).clone(),
+                None => info.path.clone(),
+            };
+            members.entry(key).or_default().push(info);
+        }
+
+        let representatives = members
+            .iter()
+            .map(|(path, members)| TargetInfo {
+                path: path.clone(),
+                bytes: members.iter().map(|info| info.bytes).sum(),
+                apparent_bytes: members.iter().map(|info| info.apparent_bytes).sum(),
+                age: members.iter().filter_map(|info| info.age).min(),
+            })
+            .collect();
+
+        Self { representatives, members }
+    }
+
+    /// The targets of the given units, in their order
+    fn expand(mut self, units: Vec<TargetInfo>) -> Vec<TargetInfo> {
+        units
+            .iter()
+            .flat_map(|unit| self.members.remove(&unit.path).unwrap_or_default())
+            .collect()
+    }
+}
+
+/// Drop the node_modules of workspaces that the filters left only partly selected
+///
+/// Deleting some of a workspace's node_modules but not the others leaves a broken install,
+/// so a workspace is either cleaned completely or not at all.
+fn skip_partial_workspaces(infos: &mut Vec<TargetInfo>, quiet: bool) {
+    let paths: Vec<PathBuf> = infos.iter().map(|info| info.path.clone()).collect();
+    let partial = workspace::partial_workspaces(&paths);
+    if partial.is_empty() {
+        return;
+    }
+
+    let skipped: HashSet<&PathBuf> = partial.iter().flat_map(|w| &w.selected).collect();
+    infos.retain(|info| std::path::absolute(&info.path).is_ok_and(|path| !skipped.contains(&path)));
+
+    if !quiet {
+        for workspace in &partial {
+            println!(
+                "Skipping workspace '{}': {} of its node_modules would be left behind",
+                workspace.root.display(),
+                workspace.missing.len()
+            );
+        }
+    }
+}
+
+/// Add the rest of every workspace of which only some node_modules were picked
+///
+/// Picking one node_modules of a workspace picks the whole workspace. If the rest of it
+/// isn't among the candidates, the workspace is left alone instead.
+fn complete_workspaces(mut selected: Vec<PathBuf>, candidates: &[TargetInfo], quiet: bool) -> Vec<PathBuf> {
+    let candidates: HashSet<PathBuf> = candidates
+        .iter()
+        .filter_map(|info| std::path::absolute(&info.path).ok())
+        .collect();
+
+    for workspace in workspace::partial_workspaces(&selected) {
+        if workspace.missing.iter().all(|path| candidates.contains(path)) {
+            if !quiet {
+                println!(
+                    "Also deleting the other {} node_modules of workspace '{}'",
+                    workspace.missing.len(),
+                    workspace.root.display()
+                );
+            }
+            selected.extend(workspace.missing);
+        } else {
+            if !quiet {
+                println!("Skipping workspace '{}': it can't be deleted as a whole", workspace.root.display());
+            }
+            selected.retain(|path| std::path::absolute(path).is_ok_and(|path| !workspace.selected.contains(&path)));
+        }
+    }
+
+    selected
+}
+
+/// Find, measure and sort the targets below `root_path` without deleting anything
+///
+/// Applies the same filters as [`cleanup_node_modules`], including the size limits, `sort`
+/// and `top`, and always measures every target.
+pub fn scan_targets<P: AsRef<std::path::Path>>(root_path: P, config: &Config) -> Result<Vec<TargetInfo>> {
+    let cleaner = cleaner::Cleaner::new(config.threads, false);
+    let targets = build_scanner(root_path.as_ref(), config).find_node_modules_dirs_parallel(cleaner.thread_pool())?;
+
+    let (mut infos, _) = measure_and_filter(targets, &cleaner, config, true);
+    cleaner::sort_targets(&mut infos, config.sort);
+    if let Some(top) = config.top {
+        infos.truncate(top);
+    }
+    Ok(infos)
+}
+
+/// Summarize the disk usage of the targets below `root_path` by project, largest first
+pub fn report_usage<P: AsRef<std::path::Path>>(root_path: P, config: &Config) -> Result<Vec<ProjectUsage>> {
+    let config = Config {
+        sort: SortOrder::Path,
+        top: None,
+        ..config.clone()
+    };
+    let mut projects: Vec<ProjectUsage> = Vec::new();
+
+    for info in scan_targets(root_path, &config)? {
+        let project = artifact::project_of(&info.path).to_path_buf();
+        // Targets are sorted by path, so a project's targets are adjacent
+        if projects.last().is_none_or(|last| last.project != project) {
+            projects.push(ProjectUsage {
+                project,
+                age_secs: info.age.map(|age| age.as_secs()),
+                ..Default::default()
+            });
+        }
+
+        if let Some(usage) = projects.last_mut() {
+            usage.bytes += info.bytes;
+            usage.apparent_bytes += info.apparent_bytes;
+            usage.targets.push(info.path);
+        }
+    }
+
+    projects.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.project.cmp(&b.project)));
+    Ok(projects)
+}
+
+/// Move trashed targets whose original location is below `root_path` back into place
+///
+/// Both the home trash and the trash of the volume holding `root_path` are searched. Only
+/// directories named like an artifact are considered. When the same location was trashed
+/// several times, the most recent copy is restored and the others are reported as failed.
+pub fn restore_trashed<P: AsRef<std::path::Path>>(root_path: P, config: &Config) -> Result<Vec<TargetOutcome>> {
+    let root_path = root_path.as_ref();
+    let home = trash::Trash::home()?;
+
+    let mut outcomes = restore_from_trash(&home, root_path, config)?;
+    if let Some(volume) = trash::Trash::for_volume_of(root_path).filter(|volume| volume.root() != home.root()) {
+        outcomes.extend(restore_from_trash(&volume, root_path, config)?);
+    }
+    Ok(outcomes)
+}
+
+/// Same as [`restore_trashed`], but using the given trash directory
+pub fn restore_from_trash<P: AsRef<std::path::Path>>(
+    trash: &trash::Trash,
+    root_path: P,
+    config: &Config,
+) -> Result<Vec<TargetOutcome>> {
+    let root = std::path::absolute(root_path.as_ref())?;
+
+    let mut items: Vec<trash::TrashedItem> = trash
+        .list()?
+        .into_iter()
+        .filter(|item| item.original.starts_with(&root))
+        .filter(|item| ArtifactKind::is_artifact_path(&item.original))
+        .collect();
+    items.sort_by(|a, b| a.original.cmp(&b.original).then_with(|| b.deleted_at.cmp(&a.deleted_at)));
+
+    let outcomes = items
+        .iter()
+        .map(|item| {
+            let result = if config.dry_run { Ok(()) } else { trash.restore(item) };
+            TargetOutcome {
+                path: item.original.clone(),
+                bytes: None,
+                apparent_bytes: None,
+                age_secs: None,
+                status: match (&result, config.dry_run) {
+                    (Ok(()), true) => TargetStatus::WouldRestore,
+                    (Ok(()), false) => TargetStatus::Restored,
+                    (Err(_), _) => TargetStatus::Failed,
+                },
+                error: result.err().map(|e| NukeError::from_anyhow(&item.original, &e)),
+            }
+        })
+        .collect();
+
+    Ok(outcomes)
+}
+
+/// Build a scanner applying the filters in `config`
+fn build_scanner(root_path: &std::path::Path, config: &Config) -> scanner::Scanner {
+    scanner::Scanner::new(root_path, &config.exclude_patterns)
+        .with_min_age(config.older_than)
+        .with_kinds(&config.kinds)
+        .with_skip_dirty(config.skip_dirty)
+        .with_follow_symlinks(config.follow_symlinks)
+        .with_one_file_system(config.one_file_system)
+}
+
+/// Measure targets in parallel if `measured` is set and drop those outside the size limits
+fn measure_and_filter(
+    targets: Vec<PathBuf>,
+    cleaner: &cleaner::Cleaner,
+    config: &Config,
+    measured: bool,
+) -> (Vec<TargetInfo>, SizeFiltered) {
+    let mut infos = if measured {
+        cleaner.measure_targets(&targets)
+    } else {
+        targets
+            .into_iter()
+            .map(|path| TargetInfo { path, bytes: 0, apparent_bytes: 0, age: None })
+            .collect()
+    };
+
+    let mut filtered = SizeFiltered::default();
+    infos.retain(|info| match config.size_rejection(info.bytes) {
+        Some(SizeRejection::TooSmall) => {
+            filtered.too_small += 1;
+            false
+        }
+        Some(SizeRejection::TooLarge) => {
+            filtered.too_large += 1;
+            false
+        }
+        None => true,
+    });
+    (infos, filtered)
+}
+
+/// Number of targets dropped by each size limit
+#[derive(Debug, Default)]
+struct SizeFiltered {
+    too_small: usize,
+    too_large: usize,
+}
+
+/// Delete the targets that the last run from `root_path` never finished, according to the journal
+///
+/// Targets that no longer exist were removed before the interruption and are skipped.
+fn resume_run(
+    root_path: &std::path::Path,
+    cleaner: &cleaner::Cleaner,
+    config: &Config,
+    quiet: bool,
+) -> Result<CleanupStats> {
+    let path = config
+        .journal
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("Resuming needs the deletion journal, which is disabled"))?;
+    let entries = journal::read_entries(path)?;
+
+    let Some((run, targets)) = journal::unfinished_targets(&entries, root_path) else {
+        if !quiet {
+            println!("Nothing to resume: the last run from '{}' finished", root_path.display());
+        }
+        return Ok(CleanupStats::default());
+    };
+    let targets: Vec<PathBuf> = targets
+        .into_iter()
+        .filter(|target| target.symlink_metadata().is_ok())
+        .collect();
+
+    if !quiet {
+        println!("Resuming run {}: {} directories left", run, targets.len());
+    }
+    if targets.is_empty() {
+        return Ok(CleanupStats::default());
+    }
+
+    if !config.dry_run && !config.no_confirm && !quiet && !cli::confirm_deletion(&targets)? {
+        if !quiet {
             println!("Aborted");
         }
         return Ok(CleanupStats {
             directories_found: targets.len(),
+            directories_selected: targets.len(),
+            ..Default::default()
+        });
+    }
+    if config.dry_run {
+        if !quiet {
+            for target in &targets {
+                println!("  {}", target.display());
+            }
+        }
+        return Ok(CleanupStats {
+            directories_found: targets.len(),
+            directories_selected: targets.len(),
             ..Default::default()
         });
     }
 
-    let cleaner = cleaner::Cleaner::new(config.threads, !config.quiet);
-    let stats = cleaner.delete_directories(targets)?;
+    cleaner.delete_directories(targets)
+}
+
+/// Delete trees that an interrupted fast-mode run renamed aside but never removed
+///
+/// Returns how many were removed; failures are only reported, since the next run retries them.
+fn finish_leftovers(scanner: &scanner::Scanner, cleaner: &cleaner::Cleaner, quiet: bool) -> usize {
+    let leftovers: Vec<PathBuf> = scanner
+        .leftovers()
+        .into_iter()
+        .filter(|path| staging::is_abandoned(path))
+        .collect();
+    if leftovers.is_empty() {
+        return 0;
+    }
+
+    if !quiet {
+        println!("Finishing {} deletions left over from an interrupted run", leftovers.len());
+    }
+
+    let failures = staging::delete_staged(&leftovers, cleaner.thread_pool());
+    for (path, e) in &failures {
+        eprintln!("Warning: Failed to delete leftover '{}': {}", path.display(), e);
+    }
+    leftovers.len() - failures.len()
+}
+
+/// Delete targets while the scan is still discovering them
+///
+/// Nothing is listed or confirmed up front, so every option that needs the complete,
+/// measured list of targets is rejected. That includes keeping workspaces together, so
+/// workspace grouping must be turned off explicitly with `--ignore-workspaces`.
+fn run_pipeline(
+    scanner: scanner::Scanner,
+    cleaner: &cleaner::Cleaner,
+    config: &Config,
+    quiet: bool,
+    noun: &str,
+) -> Result<CleanupStats> {
+    let unsupported = [
+        (!config.no_confirm, "confirmation (pass --no-confirm)"),
+        (config.dry_run, "--dry-run"),
+        (config.interactive, "--interactive"),
+        (config.sort != SortOrder::Path, "--sort"),
+        (config.top.is_some(), "--top"),
+        (config.reclaim.is_some(), "--reclaim/--until-free"),
+        (config.min_size.is_some() || config.max_size.is_some(), "size limits"),
+        (!config.ignore_workspaces, "workspace grouping (pass --ignore-workspaces)"),
+    ];
+    if let Some((_, option)) = unsupported.iter().find(|(set, _)| *set) {
+        return Err(anyhow::anyhow!("Pipelined deletion cannot be combined with {}", option));
+    }
+
+    if !quiet {
+        println!("Deleting {} directories as they are found...", noun);
+    }
+
+    let stats = cleaner.delete_stream(scanner.scan_stream())?;
+
+    if stats.directories_found == 0 && !quiet {
+        println!("No {} directories found.", noun);
+    }
 
     Ok(stats)
 }
@@ -101,4 +955,78 @@ pub fn format_bytes(bytes: u64) -> String {
     } else {
         format!("{:.1} {}", size, UNITS[unit_index])
     }
-}
\ No newline at end of file
+}
+
+/// Parse a human-friendly size such as `100M`, `1.5 GB` or `512` (bytes), using 1024-based units
+pub fn parse_bytes(input: &str) -> Result<u64> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(input.len());
+    let (number, unit) = input.split_at(split);
+
+    let value: f64 = number
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid size '{}': expected a number followed by a unit", input))?;
+
+    let exponent = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 0,
+        "K" | "KB" | "KIB" => 1,
+        "M" | "MB" | "MIB" => 2,
+        "G" | "GB" | "GIB" => 3,
+        "T" | "TB" | "TIB" => 4,
+        other => {
+            return Err(anyhow::anyhow!(
+                "Invalid size unit '{}' in '{}': use B, K, M, G or T",
+                other,
+                input
+            ))
+        }
+    };
+
+    Ok((value * 1024f64.powi(exponent)).round() as u64)
+}
+
+/// Parse a human-friendly age such as `30d`, `12h` or `2w` (a bare number means days)
+pub fn parse_age(input: &str) -> Result<Duration> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (number, unit) = input.split_at(split);
+
+    let value: u64 = number
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid age '{}': expected a number followed by a unit", input))?;
+
+    let seconds_per_unit = match unit.trim().to_ascii_lowercase().as_str() {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "" | "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        "y" => 365 * 24 * 60 * 60,
+        other => {
+            return Err(anyhow::anyhow!(
+                "Invalid age unit '{}' in '{}': use s, m, h, d, w or y",
+                other,
+                input
+            ))
+        }
+    };
+
+    Ok(Duration::from_secs(value.saturating_mul(seconds_per_unit)))
+}
+
+/// Format a duration as a coarse human-readable age (minutes, hours or days)
+pub fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+
+    if secs < 60 * 60 {
+        format!("{} minutes", secs / 60)
+    } else if secs < 24 * 60 * 60 {
+        format!("{} hours", secs / (60 * 60))
+    } else {
+        format!("{} days", secs / (24 * 60 * 60))
+    }
+}
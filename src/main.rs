@@ -2,8 +2,22 @@
 
 use anyhow::Result;
 use clap::Parser;
-use nuke_node_modules::{cleanup_node_modules, cli::Cli, format_bytes};
+use nuke_node_modules::{
+    cleaner, cleanup_node_modules,
+    cli::{Cli, Command},
+    config_file::FileConfig,
+    format_age, format_bytes, journal, report, report_usage, restore_trashed, scan_targets, staging,
+    CleanupStats, Config,
+};
+use nuke_node_modules::reinstall::{ReinstallOutcome, ReinstallStatus};
+use nuke_node_modules::report::{OutputFormat, TargetOutcome, TargetStatus};
+use std::path::Path;
 use std::process;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Exit code used when the run was interrupted with Ctrl-C
+const EXIT_INTERRUPTED: i32 = 130;
 
 fn main() {
     if let Err(e) = run() {
@@ -15,24 +29,79 @@ fn main() {
 fn run() -> Result<()> {
     let cli = Cli::parse();
 
+    // Background half of --fast: delete what the parent process renamed aside
+    if !cli.finish_deleting.is_empty() {
+        let cleaner = cleaner::Cleaner::new(cli.threads, false);
+        staging::delete_staged(&cli.finish_deleting, cleaner.thread_pool());
+        return Ok(());
+    }
+
+    if let Some(Command::History { limit }) = &cli.command {
+        return print_history(*limit, cli.format);
+    }
+
+    let root_path = cli.get_root_path();
+
+    // Merge config files with CLI args
+    let mut config = cli.load_config(&root_path)?;
+
+    if cli.print_config {
+        print!("{}", FileConfig::from_config(&config).to_toml()?);
+        return Ok(());
+    }
+
+    if let Some(Command::Restore { .. }) = &cli.command {
+        return run_restore(&root_path, &config);
+    }
+
     // Print banner and scanning info
     cli.print_banner();
+    cli.print_scan_info(&root_path, &config);
 
-    let root_path = cli.get_root_path();
-    cli.print_scan_info(&root_path);
+    match &cli.command {
+        Some(Command::Scan { .. }) => return run_scan(&root_path, &config),
+        Some(Command::Report { .. }) => return run_report(&root_path, &config),
+        _ => {}
+    }
 
-    // Convert CLI args to config
-    let config = cli.to_config();
+    // On Ctrl-C, stop before prompting or deleting, or let in-flight deletions finish; a
+    // second Ctrl-C exits immediately
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    let handler_flag = Arc::clone(&cancel_flag);
+    ctrlc::set_handler(move || {
+        if handler_flag.swap(true, Ordering::SeqCst) {
+            process::exit(EXIT_INTERRUPTED);
+        }
+        eprintln!("\n⏹  Interrupted - no new deletions will start (press Ctrl-C again to abort)");
+    })?;
+    config.cancel_flag = Some(cancel_flag);
 
     // Perform the cleanup
     let stats = cleanup_node_modules(&root_path, &config)?;
 
+    // Fast mode only renamed the targets; the actual deletion outlives this process
+    if !stats.pending_deletion.is_empty()
+        && let Err(e) = staging::spawn_background_delete(&stats.pending_deletion)
+    {
+        eprintln!("Warning: Could not start background deletion ({}), deleting now", e);
+        let cleaner = cleaner::Cleaner::new(config.threads, false);
+        for (path, e) in staging::delete_staged(&stats.pending_deletion, cleaner.thread_pool()) {
+            eprintln!("Warning: Failed to delete '{}': {}", path.display(), e);
+        }
+    }
+
     // Print final statistics if not in quiet mode
-    if !config.quiet {
+    if config.format.is_machine_readable() {
+        report::emit_results(config.format, &stats)?;
+    } else if !config.quiet {
         if config.dry_run {
             println!("🔍 Dry run completed - no files were deleted");
-        } else if stats.directories_deleted > 0 {
+        } else if stats.directories_cancelled > 0 {
+            println!("⏹  Cleanup interrupted - remaining directories were left untouched");
+        } else if stats.directories_deleted + stats.directories_trashed + stats.directories_staged > 0 {
             println!("✅ Cleanup completed successfully!");
+        } else if stats.directories_found == 0 && stats.directories_too_small + stats.directories_too_large > 0 {
+            println!("ℹ️  Nothing within the size limits to delete");
         } else if stats.directories_found == 0 {
             println!("ℹ️  No node_modules directories found");
         } else {
@@ -44,26 +113,211 @@ fn run() -> Result<()> {
             println!();
             println!("📊 Final Summary:");
             println!("   Found: {}", stats.directories_found);
+            let outside_limits = stats.directories_too_small + stats.directories_too_large;
+            if outside_limits > 0 {
+                println!("   Outside the size limits (skipped): {}", outside_limits);
+            }
 
             if config.dry_run {
-                println!("   Would delete: {}", stats.directories_found);
+                println!("   Would delete: {}", stats.directories_selected);
+                println!(
+                    "   Would free: {} on disk ({} apparent)",
+                    format_bytes(stats.bytes_reclaimable),
+                    format_bytes(stats.bytes_reclaimable_apparent)
+                );
             } else {
                 println!("   Deleted: {}", stats.directories_deleted);
+                if stats.directories_trashed > 0 {
+                    println!("   Trashed: {}", stats.directories_trashed);
+                }
                 if stats.directories_failed > 0 {
                     println!("   Failed: {}", stats.directories_failed);
                 }
+                if stats.directories_staged > 0 {
+                    println!(
+                        "   Deleting in the background: {} ({})",
+                        stats.directories_staged,
+                        format_bytes(stats.bytes_pending_deletion)
+                    );
+                }
+                if stats.leftovers_removed > 0 {
+                    println!("   Leftovers from earlier runs removed: {}", stats.leftovers_removed);
+                }
+                if stats.directories_in_use > 0 {
+                    println!("   In use (skipped): {}", stats.directories_in_use);
+                }
+                if stats.directories_cancelled > 0 {
+                    println!("   Not started: {}", stats.directories_cancelled);
+                }
                 if stats.bytes_freed > 0 {
-                    println!("   Space freed: {}", format_bytes(stats.bytes_freed));
+                    println!(
+                        "   Space freed: {} on disk ({} apparent)",
+                        format_bytes(stats.bytes_freed),
+                        format_bytes(stats.bytes_freed_apparent)
+                    );
                 }
+                if stats.bytes_trashed > 0 {
+                    println!(
+                        "   Moved to trash: {} on disk ({} apparent)",
+                        format_bytes(stats.bytes_trashed),
+                        format_bytes(stats.bytes_trashed_apparent)
+                    );
+                }
+                cleaner::print_partially_deleted(&stats);
+                print_reinstalls(&stats.reinstalls);
             }
         }
     }
 
     // Exit with appropriate code
-    if stats.directories_failed > 0 {
+    if stats.directories_cancelled > 0 {
+        process::exit(EXIT_INTERRUPTED);
+    }
+    if stats.directories_failed > 0 || stats.reinstalls.iter().any(|r| r.status == ReinstallStatus::Failed) {
         process::exit(1);
     }
 
     Ok(())
 }
 
+/// Summarize the reinstalls that followed the cleanup
+fn print_reinstalls(reinstalls: &[ReinstallOutcome]) {
+    let count = |status| reinstalls.iter().filter(|r| r.status == status).count();
+
+    if count(ReinstallStatus::Installed) > 0 {
+        println!("   Reinstalled: {}", count(ReinstallStatus::Installed));
+    }
+    if count(ReinstallStatus::NoLockfile) > 0 {
+        println!("   Not reinstalled (no lockfile): {}", count(ReinstallStatus::NoLockfile));
+    }
+    let failed: Vec<&ReinstallOutcome> = reinstalls.iter().filter(|r| r.status == ReinstallStatus::Failed).collect();
+    if !failed.is_empty() {
+        println!("   Reinstall failed: {}", failed.len());
+        for reinstall in failed {
+            println!(
+                "      {}: {}",
+                reinstall.project.display(),
+                reinstall.error.as_deref().unwrap_or("unknown error")
+            );
+        }
+    }
+}
+
+/// List past runs from the deletion journal, most recent last
+fn print_history(limit: Option<usize>, format: OutputFormat) -> Result<()> {
+    let path = journal::default_path()
+        .ok_or_else(|| anyhow::anyhow!("Cannot locate the journal: neither XDG_STATE_HOME nor HOME is set"))?;
+    let summaries = journal::summarize_runs(&journal::read_entries(&path)?);
+
+    let skip = limit.map_or(0, |limit| summaries.len().saturating_sub(limit));
+    if format.is_machine_readable() {
+        return report::emit_history(format, &summaries[skip..]);
+    }
+    journal::print_history(&summaries[skip..]);
+    Ok(())
+}
+
+/// List targets with their sizes, without prompting or deleting
+fn run_scan(root_path: &Path, config: &Config) -> Result<()> {
+    let infos = scan_targets(root_path, config)?;
+    let stats = CleanupStats {
+        directories_found: infos.len(),
+        directories_selected: infos.len(),
+        bytes_reclaimable: infos.iter().map(|info| info.bytes).sum(),
+        bytes_reclaimable_apparent: infos.iter().map(|info| info.apparent_bytes).sum(),
+        targets: infos.iter().map(TargetOutcome::would_delete).collect(),
+        ..Default::default()
+    };
+
+    match config.format {
+        OutputFormat::Json => report::emit_results(config.format, &stats)?,
+        OutputFormat::Ndjson => {
+            report::emit_outcomes(config.format, &stats.targets)?;
+            report::emit_results(config.format, &stats)?;
+        }
+        OutputFormat::Text if !config.quiet => {
+            println!("Found {} directories", stats.directories_found);
+            for info in &infos {
+                println!("  {:>10}  {}", format_bytes(info.bytes), info.path.display());
+            }
+            println!(
+                "Total: {} on disk ({} apparent)",
+                format_bytes(stats.bytes_reclaimable),
+                format_bytes(stats.bytes_reclaimable_apparent)
+            );
+        }
+        OutputFormat::Text => {}
+    }
+
+    Ok(())
+}
+
+/// Print disk usage of targets grouped by project, largest first
+fn run_report(root_path: &Path, config: &Config) -> Result<()> {
+    let projects = report_usage(root_path, config)?;
+
+    if config.format.is_machine_readable() {
+        return report::emit_usage(config.format, &projects);
+    }
+    if config.quiet {
+        return Ok(());
+    }
+
+    if projects.is_empty() {
+        println!("No directories found.");
+        return Ok(());
+    }
+
+    println!("📊 Disk usage by project (size, project age, path):");
+    for project in &projects {
+        let age = project
+            .age_secs
+            .map(|secs| format_age(std::time::Duration::from_secs(secs)))
+            .unwrap_or_else(|| "unknown".to_string());
+        let names: Vec<String> = project
+            .targets
+            .iter()
+            .filter_map(|target| target.file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+            .collect();
+        println!(
+            "  {:>10}  {:>12}  {}  ({})",
+            format_bytes(project.bytes),
+            age,
+            project.project.display(),
+            names.join(", ")
+        );
+    }
+    println!(
+        "Total: {} on disk in {} projects",
+        format_bytes(projects.iter().map(|project| project.bytes).sum()),
+        projects.len()
+    );
+
+    Ok(())
+}
+
+/// Move targets trashed from below the root back into place
+fn run_restore(root_path: &Path, config: &Config) -> Result<()> {
+    let outcomes = restore_trashed(root_path, config)?;
+
+    if config.format.is_machine_readable() {
+        report::emit_outcomes(config.format, &outcomes)?;
+    } else if !config.quiet {
+        if outcomes.is_empty() {
+            println!("Nothing below '{}' to restore from the trash", root_path.display());
+        }
+        for outcome in &outcomes {
+            match (&outcome.status, &outcome.error) {
+                (TargetStatus::WouldRestore, _) => println!("  Would restore {}", outcome.path.display()),
+                (_, Some(e)) => println!("  ⚠️  {}", e),
+                _ => println!("  ♻️  Restored {}", outcome.path.display()),
+            }
+        }
+    }
+
+    if outcomes.iter().any(|outcome| outcome.status == TargetStatus::Failed) {
+        process::exit(1);
+    }
+    Ok(())
+}
@@ -0,0 +1,89 @@
+//! Free-space goals: pick just enough targets to reclaim a given amount of space
+
+use crate::cleaner::TargetInfo;
+use anyhow::Result;
+use std::path::Path;
+
+/// How much space a run should free
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReclaimGoal {
+    /// Free at least this many bytes
+    Bytes(u64),
+    /// Free space until the filesystem has at least this many bytes available
+    UntilFree(u64),
+}
+
+impl ReclaimGoal {
+    /// Number of bytes that still need to be freed under `root` to meet this goal
+    pub fn bytes_needed(self, root: &Path) -> Result<u64> {
+        match self {
+            ReclaimGoal::Bytes(bytes) => Ok(bytes),
+            ReclaimGoal::UntilFree(bytes) => Ok(bytes.saturating_sub(available_space(root)?)),
+        }
+    }
+}
+
+/// Bytes available to unprivileged users on the filesystem containing `path`
+#[cfg(unix)]
+pub fn available_space(path: &Path) -> Result<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes())?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+
+    // SAFETY: c_path is a valid NUL-terminated string and stat is a valid out-pointer
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return Err(anyhow::anyhow!(
+            "Failed to query free space of '{}': {}",
+            path.display(),
+            std::io::Error::last_os_error()
+        ));
+    }
+
+    // Field widths differ between platforms
+    #[allow(clippy::unnecessary_cast)]
+    let available = stat.f_bavail as u64 * stat.f_frsize as u64;
+
+    Ok(available)
+}
+
+/// Bytes available on the filesystem containing `path`
+#[cfg(not(unix))]
+pub fn available_space(path: &Path) -> Result<u64> {
+    Err(anyhow::anyhow!(
+        "Querying free space of '{}' is not supported on this platform",
+        path.display()
+    ))
+}
+
+/// Rank targets oldest first (larger first when ages tie) and keep just enough to free `needed` bytes
+///
+/// Ages are compared in whole days; targets of unknown age come last.
+pub fn select_for_reclaim(mut targets: Vec<TargetInfo>, needed: u64) -> Vec<TargetInfo> {
+    const DAY: u64 = 24 * 60 * 60;
+
+    targets.sort_by(|a, b| {
+        let a_days = a.age.map(|age| age.as_secs() / DAY);
+        let b_days = b.age.map(|age| age.as_secs() / DAY);
+        a_days
+            .is_none()
+            .cmp(&b_days.is_none())
+            .then_with(|| b_days.cmp(&a_days))
+            .then_with(|| b.bytes.cmp(&a.bytes))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+
+    let mut reclaimed = 0u64;
+    let mut selected = Vec::new();
+
+    for target in targets {
+        if reclaimed >= needed {
+            break;
+        }
+        reclaimed += target.bytes;
+        selected.push(target);
+    }
+
+    selected
+}
@@ -0,0 +1,220 @@
+//! Reinstalling dependencies in projects whose node_modules were removed
+//!
+//! The package manager is detected from the project's lockfile and its clean-install
+//! command (e.g. `npm ci`) is run in the project directory. Installs run on their own
+//! thread pool, sized separately from the deletion pool, because each install is itself
+//! heavily parallel and network-bound.
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Number of installs run at once by default
+pub const DEFAULT_JOBS: usize = 1;
+
+/// A package manager that can reinstall a project from its lockfile
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageManager {
+    Npm,
+    Yarn,
+    Pnpm,
+    Bun,
+}
+
+impl PackageManager {
+    /// Lockfiles in order of preference, for projects that have more than one
+    const LOCKFILES: &[(&str, PackageManager)] = &[
+        ("pnpm-lock.yaml", PackageManager::Pnpm),
+        ("yarn.lock", PackageManager::Yarn),
+        ("bun.lockb", PackageManager::Bun),
+        ("bun.lock", PackageManager::Bun),
+        ("package-lock.json", PackageManager::Npm),
+        ("npm-shrinkwrap.json", PackageManager::Npm),
+    ];
+
+    /// Detect the package manager of a project from its lockfile
+    pub fn detect(project: &Path) -> Option<Self> {
+        Self::LOCKFILES
+            .iter()
+            .find(|(lockfile, _)| project.join(lockfile).is_file())
+            .map(|&(_, manager)| manager)
+    }
+
+    /// Executable to run
+    pub fn program(self) -> &'static str {
+        match self {
+            PackageManager::Npm => "npm",
+            PackageManager::Yarn => "yarn",
+            PackageManager::Pnpm => "pnpm",
+            PackageManager::Bun => "bun",
+        }
+    }
+
+    /// Arguments of the install command that installs exactly what the lockfile says
+    pub fn clean_install_args(self) -> &'static [&'static str] {
+        match self {
+            PackageManager::Npm => &["ci"],
+            PackageManager::Yarn | PackageManager::Pnpm | PackageManager::Bun => &["install", "--frozen-lockfile"],
+        }
+    }
+
+    /// The clean-install command as typed in a shell, e.g. `npm ci`
+    pub fn command_line(self) -> String {
+        std::iter::once(self.program())
+            .chain(self.clean_install_args().iter().copied())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl std::fmt::Display for PackageManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.program())
+    }
+}
+
+/// What happened when reinstalling a project
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReinstallStatus {
+    /// The install command succeeded
+    Installed,
+    /// The install command could not be started or exited with an error
+    Failed,
+    /// No lockfile was found, so there was nothing to install from
+    NoLockfile,
+    /// Not attempted because the run was interrupted
+    Cancelled,
+}
+
+/// Per-project result of a reinstall
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReinstallOutcome {
+    /// Project directory the install ran in
+    pub project: PathBuf,
+    /// Package manager detected from the lockfile
+    pub manager: Option<PackageManager>,
+    /// Outcome of the install
+    pub status: ReinstallStatus,
+    /// Why the install failed
+    pub error: Option<String>,
+}
+
+/// Runs clean installs in projects, a few at a time
+pub struct Reinstaller {
+    thread_pool: rayon::ThreadPool,
+    show_progress: bool,
+    search_path: Option<OsString>,
+    cancel_flag: Option<Arc<AtomicBool>>,
+}
+
+impl Reinstaller {
+    /// Create a reinstaller running up to `jobs` installs at once (None = [`DEFAULT_JOBS`])
+    pub fn new(jobs: Option<usize>, show_progress: bool) -> Self {
+        let thread_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(jobs.unwrap_or(DEFAULT_JOBS).max(1))
+            .build()
+            .expect("Failed to create thread pool");
+
+        Self {
+            thread_pool,
+            show_progress,
+            search_path: None,
+            cancel_flag: None,
+        }
+    }
+
+    /// Look package managers up in this `PATH` instead of the inherited one
+    pub fn with_search_path(mut self, search_path: Option<OsString>) -> Self {
+        self.search_path = search_path;
+        self
+    }
+
+    /// Stop starting new installs once this flag is set
+    pub fn with_cancel_flag(mut self, flag: Option<Arc<AtomicBool>>) -> Self {
+        self.cancel_flag = flag;
+        self
+    }
+
+    /// Reinstall every project, returning the outcomes in the order given
+    pub fn reinstall(&self, projects: &[PathBuf]) -> Vec<ReinstallOutcome> {
+        self.thread_pool
+            .install(|| projects.par_iter().map(|project| self.reinstall_project(project)).collect())
+    }
+
+    fn reinstall_project(&self, project: &Path) -> ReinstallOutcome {
+        let outcome = |manager, status, error| ReinstallOutcome {
+            project: project.to_path_buf(),
+            manager,
+            status,
+            error,
+        };
+
+        if self.cancel_flag.as_ref().is_some_and(|flag| flag.load(Ordering::SeqCst)) {
+            return outcome(None, ReinstallStatus::Cancelled, None);
+        }
+        let Some(manager) = PackageManager::detect(project) else {
+            return outcome(None, ReinstallStatus::NoLockfile, None);
+        };
+
+        if self.show_progress {
+            println!("📦 {} in {}", manager.command_line(), project.display());
+        }
+
+        let mut command = Command::new(manager.program());
+        command
+            .args(manager.clean_install_args())
+            .current_dir(project)
+            .stdin(Stdio::null());
+        if let Some(search_path) = &self.search_path {
+            command.env("PATH", search_path);
+        }
+
+        let result = match command.output() {
+            Ok(output) if output.status.success() => Ok(()),
+            Ok(output) => Err(format!(
+                "`{}` {}{}",
+                manager.command_line(),
+                output.status,
+                last_line(&output.stderr).map(|line| format!(": {}", line)).unwrap_or_default()
+            )),
+            Err(e) => Err(format!("could not run `{}`: {}", manager.program(), e)),
+        };
+
+        match result {
+            Ok(()) => outcome(Some(manager), ReinstallStatus::Installed, None),
+            Err(e) => {
+                if self.show_progress {
+                    eprintln!("⚠️  Reinstall failed in {}: {}", project.display(), e);
+                }
+                outcome(Some(manager), ReinstallStatus::Failed, Some(e))
+            }
+        }
+    }
+}
+
+/// Projects to reinstall after `targets` were removed: the parents of the node_modules, once each
+pub fn projects_of(targets: &[PathBuf]) -> Vec<PathBuf> {
+    let mut projects: Vec<PathBuf> = targets
+        .iter()
+        .filter(|target| target.file_name().is_some_and(|name| name == "node_modules"))
+        .filter_map(|target| target.parent().map(Path::to_path_buf))
+        .collect();
+    projects.sort();
+    projects.dedup();
+    projects
+}
+
+/// Last non-empty line of a command's output, usually the most telling part of an error
+fn last_line(output: &[u8]) -> Option<String> {
+    String::from_utf8_lossy(output)
+        .lines()
+        .map(str::trim)
+        .rfind(|line| !line.is_empty())
+        .map(str::to_string)
+}
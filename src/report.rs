@@ -0,0 +1,204 @@
+//! Machine-readable reporting of scan and cleanup results
+
+use crate::cleaner::TargetInfo;
+use crate::error::NukeError;
+use crate::journal::RunSummary;
+use crate::CleanupStats;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Output format for results
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Human-readable, colored output
+    #[default]
+    Text,
+    /// A single JSON document printed once the run finishes
+    Json,
+    /// One JSON object per line, streamed as targets are processed
+    Ndjson,
+}
+
+impl OutputFormat {
+    /// Whether this format is meant for scripts rather than humans
+    pub fn is_machine_readable(self) -> bool {
+        self != OutputFormat::Text
+    }
+}
+
+/// What happened to a single target
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetStatus {
+    /// Permanently deleted
+    Deleted,
+    /// Moved to the trash
+    Trashed,
+    /// Renamed aside in fast mode; a background process finishes deleting it
+    Staged,
+    /// Deletion was attempted and failed
+    Failed,
+    /// Deletion failed after part of the directory had already been removed
+    PartiallyDeleted,
+    /// Skipped because a running process was using it
+    InUse,
+    /// Not attempted because the run was interrupted
+    Cancelled,
+    /// Would have been deleted (dry run)
+    WouldDelete,
+    /// Moved back from the trash to its original location
+    Restored,
+    /// Would have been moved back from the trash (dry run)
+    WouldRestore,
+}
+
+/// Per-target result of a scan or cleanup
+#[derive(Debug, Clone, Serialize)]
+pub struct TargetOutcome {
+    /// Path of the target directory
+    pub path: PathBuf,
+    /// Disk space allocated to the target in bytes, if it was measured
+    pub bytes: Option<u64>,
+    /// Sum of file lengths in the target in bytes, if it was measured
+    pub apparent_bytes: Option<u64>,
+    /// Seconds since the owning project was last used, if known (only looked up for
+    /// machine-readable output)
+    pub age_secs: Option<u64>,
+    /// Outcome of the operation
+    pub status: TargetStatus,
+    /// Why the operation failed (serialized as its message)
+    #[serde(serialize_with = "serialize_error")]
+    pub error: Option<NukeError>,
+}
+
+/// Serialize an error as its message, so scripts see the same text as humans
+fn serialize_error<S: serde::Serializer>(error: &Option<NukeError>, serializer: S) -> Result<S::Ok, S::Error> {
+    match error {
+        Some(error) => serializer.collect_str(error),
+        None => serializer.serialize_none(),
+    }
+}
+
+impl TargetOutcome {
+    /// Outcome of a measured target that a dry run or scan would delete
+    pub fn would_delete(info: &TargetInfo) -> Self {
+        Self {
+            path: info.path.clone(),
+            bytes: Some(info.bytes),
+            apparent_bytes: Some(info.apparent_bytes),
+            age_secs: info.age.map(|age| age.as_secs()),
+            status: TargetStatus::WouldDelete,
+            error: None,
+        }
+    }
+
+    /// Outcome of a target left untouched because the run was interrupted first
+    pub fn cancelled(path: PathBuf) -> Self {
+        Self {
+            path,
+            bytes: None,
+            apparent_bytes: None,
+            age_secs: None,
+            status: TargetStatus::Cancelled,
+            error: None,
+        }
+    }
+}
+
+/// Disk usage of the targets of one project
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ProjectUsage {
+    /// Project directory (the parent of its targets)
+    pub project: PathBuf,
+    /// Targets inside the project
+    pub targets: Vec<PathBuf>,
+    /// Disk space allocated to the targets in bytes
+    pub bytes: u64,
+    /// Sum of file lengths in the targets in bytes
+    pub apparent_bytes: u64,
+    /// Seconds since the project was last used, if known
+    pub age_secs: Option<u64>,
+}
+
+/// A single line of NDJSON output
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Event<'a> {
+    Target(&'a TargetOutcome),
+    Summary(&'a CleanupStats),
+}
+
+/// Complete JSON document for `--format json`
+#[derive(Serialize)]
+struct Document<'a> {
+    targets: &'a [TargetOutcome],
+    stats: &'a CleanupStats,
+}
+
+/// Print a target outcome as one NDJSON line
+pub fn emit_target(outcome: &TargetOutcome) -> Result<()> {
+    println!("{}", serde_json::to_string(&Event::Target(outcome))?);
+    Ok(())
+}
+
+/// Print per-target outcomes without a summary in the given machine-readable format
+pub fn emit_outcomes(format: OutputFormat, outcomes: &[TargetOutcome]) -> Result<()> {
+    match format {
+        OutputFormat::Text => {}
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(outcomes)?),
+        OutputFormat::Ndjson => {
+            for outcome in outcomes {
+                emit_target(outcome)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Print a per-project usage report in the given machine-readable format
+pub fn emit_usage(format: OutputFormat, projects: &[ProjectUsage]) -> Result<()> {
+    match format {
+        OutputFormat::Text => {}
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(projects)?),
+        OutputFormat::Ndjson => {
+            for project in projects {
+                println!("{}", serde_json::to_string(project)?);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Print summaries of past runs in the given machine-readable format
+pub fn emit_history(format: OutputFormat, runs: &[RunSummary]) -> Result<()> {
+    match format {
+        OutputFormat::Text => {}
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(runs)?),
+        OutputFormat::Ndjson => {
+            for run in runs {
+                println!("{}", serde_json::to_string(run)?);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Print the final results in the given machine-readable format
+pub fn emit_results(format: OutputFormat, stats: &CleanupStats) -> Result<()> {
+    match format {
+        OutputFormat::Text => {}
+        OutputFormat::Json => {
+            let document = Document {
+                targets: &stats.targets,
+                stats,
+            };
+            println!("{}", serde_json::to_string_pretty(&document)?);
+        }
+        OutputFormat::Ndjson => {
+            println!("{}", serde_json::to_string(&Event::Summary(stats))?);
+        }
+    }
+    Ok(())
+}
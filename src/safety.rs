@@ -0,0 +1,129 @@
+//! Hard safety guards: dangerous scan roots and confirmation thresholds
+
+use crate::error::NukeError;
+use std::path::{Path, PathBuf};
+
+/// System directories that never hold projects; neither they nor anything below them may be a root
+#[cfg(unix)]
+const SYSTEM_TREES: &[&str] = &[
+    "/bin", "/boot", "/dev", "/etc", "/lib", "/lib32", "/lib64", "/proc", "/sbin", "/sys", "/usr",
+    "/System", "/Library", "/private/etc",
+];
+
+/// Directories that contain many unrelated trees; they may not be a root themselves
+#[cfg(unix)]
+const SHARED_ROOTS: &[&str] = &["/", "/opt", "/var", "/srv", "/home", "/Users", "/Applications"];
+
+#[cfg(windows)]
+const SYSTEM_TREES: &[&str] = &["C:\\Windows", "C:\\Program Files", "C:\\Program Files (x86)", "C:\\ProgramData"];
+
+#[cfg(windows)]
+const SHARED_ROOTS: &[&str] = &["C:\\", "C:\\Users"];
+
+#[cfg(not(any(unix, windows)))]
+const SYSTEM_TREES: &[&str] = &[];
+
+#[cfg(not(any(unix, windows)))]
+const SHARED_ROOTS: &[&str] = &[];
+
+/// Number of targets above which a second confirmation is asked for by default
+pub const DEFAULT_CONFIRM_ABOVE_COUNT: usize = 100;
+
+/// Checks that guard against deleting from places no one means to clean up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SafetyPolicy {
+    /// Allow scanning from `/`, system directories and the home directory itself
+    pub allow_dangerous_roots: bool,
+    /// Ask again before deleting more than this many targets
+    pub confirm_above_count: Option<usize>,
+    /// Ask again before deleting more than this many bytes (targets must be measured)
+    pub confirm_above_bytes: Option<u64>,
+}
+
+impl Default for SafetyPolicy {
+    fn default() -> Self {
+        Self {
+            allow_dangerous_roots: false,
+            confirm_above_count: Some(DEFAULT_CONFIRM_ABOVE_COUNT),
+            confirm_above_bytes: None,
+        }
+    }
+}
+
+impl SafetyPolicy {
+    /// Refuse a root that is `/`, a system directory or `$HOME`, unless explicitly allowed
+    pub fn check_root(&self, root: &Path) -> Result<(), NukeError> {
+        if self.allow_dangerous_roots {
+            return Ok(());
+        }
+
+        match dangerous_root_reason(root, home_dir().as_deref()) {
+            Some(reason) => Err(NukeError::SafetyViolation {
+                path: root.to_path_buf(),
+                reason: format!(
+                    "refusing to clean '{}' because it is {} (pass --i-know-what-im-doing to override)",
+                    root.display(),
+                    reason
+                ),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Why deleting `count` targets totalling `bytes` needs a second confirmation, if it does
+    pub fn second_confirmation_reason(&self, count: usize, bytes: u64) -> Option<String> {
+        if let Some(limit) = self.confirm_above_count
+            && count > limit
+        {
+            return Some(format!("{} directories is more than {}", count, limit));
+        }
+        if let Some(limit) = self.confirm_above_bytes
+            && bytes > limit
+        {
+            return Some(format!(
+                "{} is more than {}",
+                crate::format_bytes(bytes),
+                crate::format_bytes(limit)
+            ));
+        }
+        None
+    }
+}
+
+/// Describe why `root` is too dangerous to clean, given the user's home directory
+///
+/// Both paths are compared after resolving symlinks, so `/usr/local/..` or a symlink to
+/// `/` are caught too.
+pub fn dangerous_root_reason(root: &Path, home: Option<&Path>) -> Option<String> {
+    let root = resolve(root);
+
+    if root.parent().is_none() {
+        return Some("the filesystem root".to_string());
+    }
+    if home.is_some_and(|home| root == resolve(home)) {
+        return Some("your home directory".to_string());
+    }
+    if let Some(dir) = SYSTEM_TREES.iter().map(Path::new).find(|dir| root.starts_with(dir)) {
+        return Some(format!("inside the system directory '{}'", dir.display()));
+    }
+    if SHARED_ROOTS.iter().any(|dir| root == Path::new(dir)) {
+        return Some("a system directory".to_string());
+    }
+    None
+}
+
+/// Canonical form of a path, or its absolute form if it can't be resolved
+fn resolve(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path)
+        .or_else(|_| std::path::absolute(path))
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// The current user's home directory
+fn home_dir() -> Option<PathBuf> {
+    ["HOME", "USERPROFILE"]
+        .into_iter()
+        .filter_map(std::env::var_os)
+        .find(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
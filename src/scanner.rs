@@ -1,14 +1,81 @@
-//! Directory scanning functionality for finding node_modules directories
+//! Directory scanning functionality for finding node_modules and other artifact directories
 
+use crate::artifact::ArtifactKind;
+use crate::error::NukeError;
+use crate::git::{Index, Repository};
+use crate::ignore_file::IgnoreRules;
+use crate::staging;
 use anyhow::Result;
 use glob::Pattern;
+use std::collections::{HashMap, HashSet};
+use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
 use walkdir::WalkDir;
 
-/// Scanner for finding node_modules directories
+/// Scanner for finding node_modules (and other artifact) directories
 pub struct Scanner {
     root_path: PathBuf,
     exclude_patterns: Vec<Pattern>,
+    prune_patterns: Vec<Pattern>,
+    min_age: Option<Duration>,
+    kinds: Vec<ArtifactKind>,
+    skip_dirty: bool,
+    follow_symlinks: bool,
+    one_file_system: bool,
+    canonical_root: Option<PathBuf>,
+    repos: Mutex<HashMap<PathBuf, Arc<RepoState>>>,
+    leftovers: Mutex<Vec<PathBuf>>,
+}
+
+/// Targets found by [`Scanner::scan_stream`], in discovery order
+pub struct ScanStream {
+    receiver: mpsc::Receiver<Result<PathBuf>>,
+}
+
+impl Iterator for ScanStream {
+    type Item = Result<PathBuf>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.receiver.recv().ok()
+    }
+}
+
+/// Shared state of one parallel walk
+struct ParallelWalk {
+    targets: Mutex<Vec<PathBuf>>,
+    error: Mutex<Option<anyhow::Error>>,
+    root_device: Option<u64>,
+}
+
+/// What a walker should do with a directory
+enum Visit {
+    /// Report it as a target and don't descend
+    Accept,
+    /// Don't descend (a filtered-out target or a dependency tree)
+    Skip,
+    /// Descend into it
+    Descend,
+}
+
+/// What the scanner learned about one git working tree, read once per scan
+struct RepoState {
+    repo: Repository,
+    index: Option<Index>,
+    dirty: OnceLock<bool>,
+}
+
+impl RepoState {
+    /// Whether the working tree has uncommitted changes; an unreadable index counts as dirty
+    fn is_dirty(&self) -> bool {
+        *self.dirty.get_or_init(|| {
+            let work_tree = self.repo.work_tree();
+            self.index
+                .as_ref()
+                .is_none_or(|index| index.has_changes(work_tree) || index.has_untracked(work_tree))
+        })
+    }
 }
 
 impl Scanner {
@@ -21,56 +88,352 @@ impl Scanner {
                     .map_err(|e| eprintln!("Warning: Invalid pattern '{}': {}", pattern, e))
                     .ok()
             })
-            .collect();
+            .collect::<Vec<_>>();
+        let prune_patterns = compiled_patterns.iter().filter_map(prune_patterns).flatten().collect();
 
         Self {
             root_path: root_path.as_ref().to_path_buf(),
             exclude_patterns: compiled_patterns,
+            prune_patterns,
+            min_age: None,
+            kinds: vec![ArtifactKind::NodeModules],
+            skip_dirty: false,
+            follow_symlinks: false,
+            one_file_system: false,
+            canonical_root: fs::canonicalize(root_path.as_ref()).ok(),
+            repos: Mutex::new(HashMap::new()),
+            leftovers: Mutex::new(Vec::new()),
         }
     }
 
-    /// Find all node_modules directories, applying exclusion filters
+    /// Look for the given artifact kinds instead of only node_modules (empty keeps the default)
+    pub fn with_kinds(mut self, kinds: &[ArtifactKind]) -> Self {
+        if !kinds.is_empty() {
+            self.kinds = kinds.to_vec();
+        }
+        self
+    }
+
+    /// Only report targets whose project has been unused for at least `min_age`
+    pub fn with_min_age(mut self, min_age: Option<Duration>) -> Self {
+        self.min_age = min_age;
+        self
+    }
+
+    /// Also skip targets in git repositories with uncommitted changes
+    pub fn with_skip_dirty(mut self, skip_dirty: bool) -> Self {
+        self.skip_dirty = skip_dirty;
+        self
+    }
+
+    /// Descend into symlinked directories; targets reached through them must still resolve inside the root
+    pub fn with_follow_symlinks(mut self, follow_symlinks: bool) -> Self {
+        self.follow_symlinks = follow_symlinks;
+        self
+    }
+
+    /// Don't cross into other filesystems (mount points) below the root
+    pub fn with_one_file_system(mut self, one_file_system: bool) -> Self {
+        self.one_file_system = one_file_system;
+        self
+    }
+
+    /// Find all directories of the enabled artifact kinds, applying exclusion filters
+    ///
+    /// Targets are never descended into, and neither is any node_modules directory,
+    /// so nested dependency trees are not reported. Subtrees matched by a `.nukeignore`,
+    /// or whose every path would match an exclusion pattern, are pruned without being walked.
+    /// A symlink to a directory named like a target (e.g. from `npm link`) is reported as a
+    /// target itself.
     pub fn find_node_modules_dirs(&self) -> Result<Vec<PathBuf>> {
         let mut targets = Vec::new();
+        self.walk(|target| {
+            targets.push(target);
+            true
+        })?;
+
+        // Sort for consistent ordering
+        targets.sort();
+        self.dedupe_linked(&mut targets);
+        Ok(targets)
+    }
+
+    /// Scan on a background thread, yielding each target as soon as it is found
+    ///
+    /// Targets arrive in walk order rather than sorted. A walk error is yielded as the
+    /// last item. Dropping the stream stops the scan.
+    pub fn scan_stream(self) -> ScanStream {
+        let (sender, receiver) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            let mut seen = HashSet::new();
+            let result = self.walk(|target| {
+                if self.follow_symlinks && !seen.insert(fs::canonicalize(&target).unwrap_or_else(|_| target.clone())) {
+                    return true;
+                }
+                sender.send(Ok(target)).is_ok()
+            });
+            if let Err(e) = result {
+                let _ = sender.send(Err(e));
+            }
+        });
+
+        ScanStream { receiver }
+    }
 
-        for entry in WalkDir::new(&self.root_path)
+    /// Walk the tree, passing each accepted target to `on_target` until it returns false
+    fn walk(&self, mut on_target: impl FnMut(PathBuf) -> bool) -> Result<()> {
+        // Rules in effect for each directory on the current path, with its depth
+        let mut ignore_stack: Vec<(usize, Arc<IgnoreRules>)> = Vec::new();
+        let mut walker = WalkDir::new(&self.root_path)
+            .follow_links(self.follow_symlinks)
+            .same_file_system(self.one_file_system)
             .into_iter()
-            .filter_entry(|e| {
-                // Don't traverse into directories if we're already inside a node_modules directory
-                // Check if any parent in the path is named "node_modules"
-                let components: Vec<&str> = e.path().components()
-                    .filter_map(|c| c.as_os_str().to_str())
-                    .collect();
-
-                // If we find "node_modules" in the path components,
-                // don't traverse deeper unless this is the node_modules directory itself
-                if let Some(node_modules_index) = components.iter().position(|&c| c == "node_modules") {
-                    // If this entry is the node_modules directory itself, allow it
-                    // But don't traverse into it
-                    e.file_name() != "node_modules" || components.len() == node_modules_index + 1
-                } else {
-                    // No node_modules in path, allow traversal
-                    true
+            .filter_entry(|entry| {
+                let is_dir = entry.file_type().is_dir();
+                let is_file = entry.file_type().is_file();
+                if (is_file && !self.may_be_file_target(entry.path())) || (!is_dir && !is_file && !entry.path_is_symlink()) {
+                    return true;
                 }
-            })
-        {
-            let entry = entry?;
-            let path = entry.path();
+                if entry.depth() > 0 && self.should_prune(entry.path()) {
+                    return false;
+                }
+
+                let depth = entry.depth();
+                while ignore_stack.last().is_some_and(|(d, _)| *d >= depth) {
+                    ignore_stack.pop();
+                }
+
+                let rules = match ignore_stack.last() {
+                    None => IgnoreRules::root(entry.path()),
+                    Some((_, parent)) if parent.is_ignored(entry.path(), !is_file) => return false,
+                    // Targets are never entered, so their ignore files don't matter
+                    Some((_, parent)) if !is_dir || self.is_enabled_target(entry.path()) => Arc::clone(parent),
+                    Some((_, parent)) => parent.descend(entry.path()),
+                };
+                if is_dir {
+                    ignore_stack.push((depth, rules));
+                }
+                true
+            });
 
-            // Check if this is a node_modules directory
-            if entry.file_type().is_dir() && path.file_name() == Some("node_modules".as_ref()) {
-                // Apply exclusion filters
-                if !self.should_exclude(path) {
-                    targets.push(path.to_path_buf());
+        while let Some(entry) = walker.next() {
+            let entry = match entry {
+                // Followed symlinks can point back at an ancestor
+                Err(e) if e.loop_ancestor().is_some() => continue,
+                entry => entry?,
+            };
+            if !entry.file_type().is_dir() {
+                // Symlinks that are not followed, and files such as `.pnp.cjs`, can be targets themselves
+                if (entry.path_is_symlink() || self.may_be_file_target(entry.path()))
+                    && matches!(self.visit(entry.path()), Visit::Accept)
+                    && !on_target(entry.path().to_path_buf())
+                {
+                    return Ok(());
                 }
+                continue;
+            }
+
+            match self.visit(entry.path()) {
+                Visit::Accept => {
+                    if !on_target(entry.path().to_path_buf()) {
+                        return Ok(());
+                    }
+                    walker.skip_current_dir();
+                }
+                Visit::Skip => walker.skip_current_dir(),
+                Visit::Descend => {}
             }
         }
 
-        // Sort for consistent ordering
+        Ok(())
+    }
+
+    /// Same as [`find_node_modules_dirs`](Self::find_node_modules_dirs), but reads
+    /// directories in parallel on the given pool
+    ///
+    /// Every directory becomes a task on the pool, so idle threads steal subtrees from
+    /// busy ones. The first error encountered stops the walk and is returned.
+    pub fn find_node_modules_dirs_parallel(&self, pool: &rayon::ThreadPool) -> Result<Vec<PathBuf>> {
+        // Spawning a task per directory only pays off with more than one thread
+        if pool.current_num_threads() == 1 {
+            return self.find_node_modules_dirs();
+        }
+
+        // The root is never pruned, but may itself be a target
+        match self.visit(&self.root_path) {
+            Visit::Accept => return Ok(vec![self.root_path.clone()]),
+            Visit::Skip => return Ok(Vec::new()),
+            Visit::Descend => {}
+        }
+
+        let state = ParallelWalk {
+            targets: Mutex::new(Vec::new()),
+            error: Mutex::new(None),
+            root_device: device_of(&self.root_path),
+        };
+        let rules = IgnoreRules::root(&self.root_path);
+        let ancestors = self.ancestors_of(&self.root_path, &Arc::new(Vec::new()));
+        pool.scope(|scope| self.walk_parallel(scope, self.root_path.clone(), rules, ancestors, &state));
+
+        if let Some(e) = state.error.into_inner().ok().flatten() {
+            return Err(e);
+        }
+
+        let mut targets = state.targets.into_inner().unwrap_or_default();
         targets.sort();
+        self.dedupe_linked(&mut targets);
         Ok(targets)
     }
 
+    /// Visit the subdirectories of `dir`, spawning a task for each one to descend into
+    fn walk_parallel<'s>(
+        &'s self,
+        scope: &rayon::Scope<'s>,
+        dir: PathBuf,
+        rules: Arc<IgnoreRules>,
+        ancestors: Arc<Vec<PathBuf>>,
+        state: &'s ParallelWalk,
+    ) {
+        let record = |e: std::io::Error| {
+            if let Ok(mut error) = state.error.lock() {
+                error.get_or_insert_with(|| {
+                    anyhow::Error::new(e).context(format!("Failed to read directory '{}'", dir.display()))
+                });
+            }
+        };
+
+        if state.error.lock().is_ok_and(|error| error.is_some()) {
+            return;
+        }
+
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) => return record(e),
+        };
+
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => return record(e),
+            };
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+
+            let path = entry.path();
+            let is_symlink = file_type.is_symlink();
+            let is_dir = file_type.is_dir() || is_symlink && path.is_dir();
+            if !is_dir && !self.may_be_file_target(&path) {
+                continue;
+            }
+            if self.should_prune(&path) || rules.is_ignored(&path, is_dir) {
+                continue;
+            }
+            if self.one_file_system && state.root_device.is_some() && device_of(&path) != state.root_device {
+                continue;
+            }
+
+            match self.visit(&path) {
+                Visit::Accept => {
+                    if let Ok(mut targets) = state.targets.lock() {
+                        targets.push(path);
+                    }
+                }
+                Visit::Skip => {}
+                Visit::Descend if !is_dir => {}
+                Visit::Descend => {
+                    if is_symlink && !self.follow_symlinks {
+                        continue;
+                    }
+                    // Followed symlinks can point back at an ancestor
+                    if is_symlink
+                        && fs::canonicalize(&path).is_ok_and(|canonical| ancestors.contains(&canonical))
+                    {
+                        continue;
+                    }
+
+                    let rules = rules.descend(&path);
+                    let ancestors = self.ancestors_of(&path, &ancestors);
+                    scope.spawn(move |scope| self.walk_parallel(scope, path, rules, ancestors, state));
+                }
+            }
+        }
+    }
+
+    /// Canonical paths of `dir` and its ancestors, tracked only when following symlinks
+    fn ancestors_of(&self, dir: &Path, parent: &Arc<Vec<PathBuf>>) -> Arc<Vec<PathBuf>> {
+        if !self.follow_symlinks {
+            return Arc::clone(parent);
+        }
+
+        let mut ancestors = parent.as_ref().clone();
+        ancestors.extend(fs::canonicalize(dir).ok());
+        Arc::new(ancestors)
+    }
+
+    /// Drop targets reached a second time through a followed symlink, keeping the first path
+    fn dedupe_linked(&self, targets: &mut Vec<PathBuf>) {
+        if !self.follow_symlinks {
+            return;
+        }
+
+        let mut seen = HashSet::new();
+        targets.retain(|target| seen.insert(fs::canonicalize(target).unwrap_or_else(|_| target.clone())));
+    }
+
+    /// Decide what a walker does with a directory (or symlink) that survived pruning
+    fn visit(&self, path: &Path) -> Visit {
+        if staging::is_staged(path) && path.is_dir() {
+            // Remember trees a fast-mode run left behind, but never look inside them
+            if let Ok(mut leftovers) = self.leftovers.lock() {
+                leftovers.push(path.to_path_buf());
+            }
+            Visit::Skip
+        } else if self.is_enabled_target(path) {
+            // Apply exclusion, age, git and root containment filters
+            if !self.should_exclude(path)
+                && self.is_old_enough(path)
+                && !self.is_protected_by_git(path)
+                && self.is_inside_root(path)
+            {
+                Visit::Accept
+            } else {
+                Visit::Skip
+            }
+        } else if path.file_name() == Some("node_modules".as_ref()) {
+            // Dependencies may contain directories that look like artifacts
+            Visit::Skip
+        } else {
+            Visit::Descend
+        }
+    }
+
+    /// Trees staged for deletion by an earlier fast-mode run, found by the last scan
+    pub fn leftovers(&self) -> Vec<PathBuf> {
+        let mut leftovers = self.leftovers.lock().map(|l| l.clone()).unwrap_or_default();
+        leftovers.sort();
+        leftovers
+    }
+
+    /// Whether a file is named like a target of an enabled file kind (e.g. `.pnp.cjs`)
+    fn may_be_file_target(&self, path: &Path) -> bool {
+        self.kinds.iter().any(|kind| kind.is_file() && path.ends_with(kind.dir_name()))
+    }
+
+    /// Whether `path` is of an enabled kind: a file for file kinds, a directory otherwise
+    ///
+    /// Directories merely named like a target, e.g. a `target` without a `Cargo.toml`, are not.
+    fn is_enabled_target(&self, path: &Path) -> bool {
+        self.kind_of(path)
+            .is_some_and(|kind| if kind.is_file() { path.is_file() } else { path.is_dir() })
+    }
+
+    /// Which enabled artifact kind a directory belongs to, if any
+    pub fn kind_of(&self, path: &Path) -> Option<ArtifactKind> {
+        self.kinds.iter().copied().find(|kind| kind.matches(path))
+    }
+
     /// Check if a path should be excluded based on the exclusion patterns
     pub fn should_exclude(&self, path: &Path) -> bool {
         let path_str = path.to_string_lossy();
@@ -84,6 +447,80 @@ impl Scanner {
         false
     }
 
+    /// Check if everything below a directory is excluded, so it need not be walked
+    pub fn should_prune(&self, dir: &Path) -> bool {
+        let dir_str = dir.to_string_lossy();
+        self.prune_patterns.iter().any(|pattern| pattern.matches(&dir_str))
+    }
+
+    /// Check whether the project owning a target satisfies the minimum age filter
+    ///
+    /// Projects whose age cannot be determined (no files besides node_modules) are kept.
+    pub fn is_old_enough(&self, target: &Path) -> bool {
+        match (self.min_age, project_age(target)) {
+            (Some(min_age), Some(age)) => age >= min_age,
+            _ => true,
+        }
+    }
+
+    /// Check that a target's own location (not what a symlinked target points to) is inside the root
+    ///
+    /// Only followed symlinks can lead outside the root, so other walks always pass.
+    pub fn is_inside_root(&self, target: &Path) -> bool {
+        if !self.follow_symlinks {
+            return true;
+        }
+
+        match (&self.canonical_root, canonical_location(target)) {
+            (Some(root), Some(location)) if location.starts_with(root) => true,
+            (_, location) => {
+                eprintln!(
+                    "Warning: Skipping '{}': it resolves to '{}', outside the scan root",
+                    target.display(),
+                    location.unwrap_or_default().display()
+                );
+                false
+            }
+        }
+    }
+
+    /// Check whether git says a target must be kept
+    ///
+    /// Targets tracked by git (vendored dependencies) are always kept. With `skip_dirty`,
+    /// so is every target inside a working tree with uncommitted changes.
+    pub fn is_protected_by_git(&self, target: &Path) -> bool {
+        let Some(state) = self.repo_state(target) else {
+            return false;
+        };
+
+        let tracked = match (&state.index, target_relative_to(target, state.repo.work_tree())) {
+            (Some(index), Some(relative)) => index.tracks(&relative),
+            _ => false,
+        };
+
+        tracked || (self.skip_dirty && state.is_dirty())
+    }
+
+    /// Look up (or read and cache) the repository containing a target
+    fn repo_state(&self, target: &Path) -> Option<Arc<RepoState>> {
+        let repo = Repository::discover(target.parent()?)?;
+        let mut repos = self.repos.lock().ok()?;
+
+        let state = repos.entry(repo.work_tree().to_path_buf()).or_insert_with(|| {
+            let index = repo
+                .read_index()
+                .map_err(|e| eprintln!("Warning: {:#}", e))
+                .ok();
+            Arc::new(RepoState {
+                repo,
+                index,
+                dirty: OnceLock::new(),
+            })
+        });
+
+        Some(Arc::clone(state))
+    }
+
     /// Get a preview of directories that would be affected (for display purposes)
     pub fn get_parent_directories(&self, paths: &[PathBuf]) -> Vec<PathBuf> {
         paths
@@ -98,15 +535,140 @@ impl Scanner {
     }
 }
 
-/// Validate that all paths end with "node_modules" for safety
-pub fn validate_targets(paths: &[PathBuf]) -> Result<()> {
+/// Derive patterns matching the directories whose descendants all match `pattern`
+///
+/// `prefix/**` and `prefix*` match every path below a directory matched by `prefix`, or
+/// by the pattern itself (`*` also matches `/` with the default match options). Other
+/// patterns can't be used to prune and only apply to the targets themselves.
+fn prune_patterns(pattern: &Pattern) -> Option<[Pattern; 2]> {
+    let source = pattern.as_str();
+    let prefix = source
+        .strip_suffix("/**")
+        .or_else(|| source.strip_suffix('*'))
+        .filter(|prefix| !prefix.is_empty() && !prefix.ends_with('/'))?;
+
+    Some([Pattern::new(prefix).ok()?, pattern.clone()])
+}
+
+/// Device id of the filesystem containing `path`
+#[cfg(unix)]
+fn device_of(path: &Path) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    fs::metadata(path).ok().map(|metadata| metadata.dev())
+}
+
+/// Device id of the filesystem containing `path` (not available on this platform)
+#[cfg(not(unix))]
+fn device_of(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// Resolve where a path itself lives, without following it if it is a symlink
+pub fn canonical_location(path: &Path) -> Option<PathBuf> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    Some(fs::canonicalize(parent).ok()?.join(path.file_name()?))
+}
+
+/// Validate that every path is a recognised artifact directory for safety
+///
+/// Each path must carry the directory name of a known kind, and that kind's
+/// marker file (e.g. `Cargo.toml` for `target`) must exist next to it.
+pub fn validate_targets(paths: &[PathBuf]) -> Result<(), NukeError> {
     for path in paths {
-        if path.file_name() != Some("node_modules".as_ref()) {
-            return Err(anyhow::anyhow!(
-                "Safety check failed: path '{}' does not end with 'node_modules'",
-                path.display()
-            ));
+        if ArtifactKind::from_path(path).is_none() {
+            return Err(NukeError::SafetyViolation {
+                path: path.clone(),
+                reason: format!(
+                    "path '{}' is not a node_modules or recognised artifact directory",
+                    path.display()
+                ),
+            });
         }
     }
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Validate that no target escapes the scan root
+///
+/// The canonical path of each target must lie inside the canonical root. When
+/// `through_symlinks` is set, symlinked targets are judged by what they point to, since
+/// that is what will be deleted; otherwise by where the link itself lives.
+pub fn validate_within_root(paths: &[PathBuf], root: &Path, through_symlinks: bool) -> Result<(), NukeError> {
+    let root = fs::canonicalize(root).map_err(|e| NukeError::SafetyViolation {
+        path: root.to_path_buf(),
+        reason: format!("cannot resolve scan root '{}': {}", root.display(), e),
+    })?;
+
+    for path in paths {
+        let resolved = if through_symlinks {
+            fs::canonicalize(path).ok()
+        } else {
+            canonical_location(path)
+        };
+
+        match resolved {
+            Some(resolved) if resolved.starts_with(&root) => {}
+            resolved => {
+                return Err(NukeError::SafetyViolation {
+                    path: path.clone(),
+                    reason: format!(
+                        "path '{}' resolves to '{}', outside '{}'",
+                        path.display(),
+                        resolved.unwrap_or_default().display(),
+                        root.display()
+                    ),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Path of `target` relative to a working tree root
+fn target_relative_to(target: &Path, work_tree: &Path) -> Option<PathBuf> {
+    let target = std::path::absolute(target).ok()?;
+    target.strip_prefix(work_tree).ok().map(Path::to_path_buf)
+}
+
+/// How many levels below the project directory [`project_last_used`] looks for source files
+const SOURCE_DEPTH: usize = 4;
+
+/// Find when the project owning `target` was last used
+///
+/// This is the newest mtime among the files directly in the project directory (its manifest,
+/// lockfiles and configuration) and the source files at most [`SOURCE_DEPTH`] levels down,
+/// ignoring artifact directories such as node_modules and hidden directories such as `.git`.
+/// Deeper files are not looked at, so that large source trees are not walked in full.
+pub fn project_last_used(target: &Path) -> Option<SystemTime> {
+    let project_dir = crate::artifact::project_of(target);
+    let mut newest: Option<SystemTime> = None;
+
+    for entry in WalkDir::new(project_dir)
+        .min_depth(1)
+        .max_depth(SOURCE_DEPTH)
+        .into_iter()
+        .filter_entry(|e| {
+            let name = e.file_name().to_string_lossy();
+            !(e.file_type().is_dir() && (ArtifactKind::is_artifact_name(&name) || name.starts_with('.')))
+        })
+        .filter_map(|e| e.ok())
+    {
+        if entry.file_type().is_file()
+            && let Some(modified) = entry.metadata().ok().and_then(|m| m.modified().ok())
+            && newest.is_none_or(|current| modified > current)
+        {
+            newest = Some(modified);
+        }
+    }
+
+    newest
+}
+
+/// How long ago the project owning `target` was last used
+pub fn project_age(target: &Path) -> Option<Duration> {
+    let last_used = project_last_used(target)?;
+    Some(SystemTime::now().duration_since(last_used).unwrap_or_default())
+}
@@ -0,0 +1,177 @@
+//! Rename-then-delete: targets are renamed out of the way at once and deleted later
+//!
+//! A target such as `app/node_modules` is renamed to the hidden sibling
+//! `app/.node_modules.nuking-<pid>`, so the project can be reinstalled immediately while the
+//! old tree is removed in the background. Staged trees left behind by a crashed or killed
+//! run are found by the next scan and finished then.
+//!
+//! A run that stages trees holds a shared lock on its [`lock_path`], which the background
+//! process inherits and keeps until it is done, so trees still being deleted after the run
+//! exited are not mistaken for abandoned ones.
+
+use rayon::prelude::*;
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+/// Marker between the original name and the pid of the run that staged a tree
+pub const STAGING_MARKER: &str = ".nuking-";
+
+/// Hidden command-line flag that makes the binary delete the staged trees it is given
+pub const FINISH_DELETING_FLAG: &str = "--finish-deleting";
+
+/// Rename a target to a hidden sibling and return its new path
+///
+/// The rename stays within the parent directory, so it is atomic and instant.
+pub fn stage(target: &Path) -> io::Result<PathBuf> {
+    run_lock();
+
+    let parent = target.parent().unwrap_or(Path::new("."));
+    let name = target
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "target has no file name"))?
+        .to_string_lossy();
+
+    let base = format!(".{}{}{}", name, STAGING_MARKER, std::process::id());
+    let mut staged = parent.join(&base);
+    let mut counter = 1;
+    while fs::symlink_metadata(&staged).is_ok() {
+        staged = parent.join(format!("{}-{}", base, counter));
+        counter += 1;
+    }
+
+    fs::rename(target, &staged)?;
+    Ok(staged)
+}
+
+/// Whether a path is named like a tree staged for deletion
+pub fn is_staged(path: &Path) -> bool {
+    staging_pid(path).is_some()
+}
+
+/// Pid of the run that staged a tree, parsed from its name
+fn staging_pid(path: &Path) -> Option<u32> {
+    let name = path.file_name()?.to_str()?;
+    let (original, suffix) = name.strip_prefix('.')?.rsplit_once(STAGING_MARKER)?;
+    if original.is_empty() {
+        return None;
+    }
+
+    let pid = suffix.split_once('-').map_or(suffix, |(pid, counter)| {
+        if counter.chars().all(|c| c.is_ascii_digit()) { pid } else { "" }
+    });
+    pid.parse().ok()
+}
+
+/// Whether a staged tree was left behind, i.e. neither the run that staged it nor its
+/// background deletion is still alive
+pub fn is_abandoned(path: &Path) -> bool {
+    staging_pid(path).is_some_and(|pid| pid != std::process::id() && !process_exists(pid) && !is_locked(pid))
+}
+
+/// Lock file of the run with the given pid, held while its staged trees are being deleted
+pub fn lock_path(pid: u32) -> PathBuf {
+    std::env::temp_dir().join(format!("nuke-node-modules-{}.lock", pid))
+}
+
+/// This run's lock, taken when it first stages a tree and held until it exits
+fn run_lock() -> Option<&'static File> {
+    static LOCK: OnceLock<Option<File>> = OnceLock::new();
+
+    LOCK.get_or_init(|| {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(lock_path(std::process::id()))
+            .ok()?;
+        file.lock_shared().ok()?;
+        Some(file)
+    })
+    .as_ref()
+}
+
+/// Whether some process still holds the lock of the run with the given pid
+fn is_locked(pid: u32) -> bool {
+    let Ok(file) = File::open(lock_path(pid)) else {
+        return false;
+    };
+    // The exclusive lock is released again when `file` is closed
+    matches!(file.try_lock(), Err(fs::TryLockError::WouldBlock))
+}
+
+#[cfg(unix)]
+fn process_exists(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    if pid <= 0 {
+        return false;
+    }
+
+    // SAFETY: signal 0 only checks whether the process exists and may be signalled
+    if unsafe { libc::kill(pid, 0) } == 0 {
+        return true;
+    }
+    io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+fn process_exists(_pid: u32) -> bool {
+    false
+}
+
+/// Delete staged trees in parallel on `pool`, returning the ones that could not be removed
+///
+/// Trees already gone (e.g. removed concurrently by another run) count as removed. The lock
+/// files of the runs that staged them are removed afterwards.
+pub fn delete_staged(paths: &[PathBuf], pool: &rayon::ThreadPool) -> Vec<(PathBuf, io::Error)> {
+    let failures = pool.install(|| {
+        paths
+            .par_iter()
+            .filter(|path| is_staged(path))
+            .filter_map(|path| match fs::remove_dir_all(path) {
+                Ok(()) => None,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+                Err(e) => Some((path.clone(), e)),
+            })
+            .collect()
+    });
+
+    let runs: HashSet<u32> = paths.iter().filter_map(|path| staging_pid(path)).collect();
+    for pid in runs {
+        let _ = fs::remove_file(lock_path(pid));
+    }
+
+    failures
+}
+
+/// Delete staged trees from a detached copy of the running executable
+///
+/// The child is put in its own process group with no output, so it outlives this process
+/// and is not interrupted by a Ctrl-C sent to the terminal. Its stdin is this run's lock,
+/// which keeps the lock held until the child exits.
+pub fn spawn_background_delete(paths: &[PathBuf]) -> io::Result<()> {
+    let stdin = run_lock()
+        .and_then(|lock| lock.try_clone().ok())
+        .map_or_else(Stdio::null, Stdio::from);
+
+    let mut command = Command::new(std::env::current_exe()?);
+    command
+        .arg(FINISH_DELETING_FLAG)
+        .args(paths)
+        .stdin(stdin)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+
+    command.spawn().map(drop)
+}
@@ -0,0 +1,345 @@
+//! Moving directories into the XDG trash instead of deleting them
+//!
+//! Things are moved, never copied, so those on another filesystem than the home trash go
+//! to the trash at the top of their own volume, as the freedesktop.org spec describes.
+
+use anyhow::{Context, Result};
+use std::ffi::{OsStr, OsString};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+/// Something in the trash, as described by its `.trashinfo` entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrashedItem {
+    /// Where it was before being trashed
+    pub original: PathBuf,
+    /// Where it is now
+    pub location: PathBuf,
+    /// Its `.trashinfo` entry
+    pub info_path: PathBuf,
+    /// When it was trashed (`YYYY-MM-DDThh:mm:ss`, local time), if recorded
+    pub deleted_at: Option<String>,
+}
+
+/// A freedesktop.org trash directory (`files/` plus `info/`)
+#[derive(Debug, Clone)]
+pub struct Trash {
+    root: PathBuf,
+}
+
+impl Trash {
+    /// Use the trash directory rooted at the given path
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Locate the home trash (`$XDG_DATA_HOME/Trash`, falling back to `~/.local/share/Trash`)
+    pub fn home() -> Result<Self> {
+        if let Some(data_home) = std::env::var_os("XDG_DATA_HOME").filter(|v| !v.is_empty()) {
+            return Ok(Self::new(PathBuf::from(data_home).join("Trash")));
+        }
+
+        let home = std::env::var_os("HOME")
+            .filter(|v| !v.is_empty())
+            .context("Cannot locate trash: neither XDG_DATA_HOME nor HOME is set")?;
+
+        Ok(Self::new(PathBuf::from(home).join(".local/share/Trash")))
+    }
+
+    /// The trash at the top of the volume (mount) holding `path`
+    ///
+    /// Following the spec, that is `$topdir/.Trash/$uid` when an administrator set up a shared,
+    /// sticky `$topdir/.Trash`, and `$topdir/.Trash-$uid` otherwise. Nothing is created.
+    /// Volume trashes only exist on Unix, so elsewhere this is `None`.
+    pub fn for_volume_of(path: &Path) -> Option<Self> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+            let path = std::path::absolute(path).ok()?;
+            let device = fs::metadata(&path).ok()?.dev();
+            let mut top = path.as_path();
+            for dir in path.ancestors().skip(1) {
+                if fs::metadata(dir).ok()?.dev() != device {
+                    break;
+                }
+                top = dir;
+            }
+
+            // SAFETY: getuid has no preconditions and cannot fail
+            let uid = unsafe { libc::getuid() };
+            let shared = top.join(".Trash");
+            let shared_is_usable = fs::symlink_metadata(&shared)
+                .is_ok_and(|metadata| metadata.is_dir() && metadata.permissions().mode() & 0o1000 != 0);
+
+            Some(if shared_is_usable {
+                Self::new(shared.join(uid.to_string()))
+            } else {
+                Self::new(top.join(format!(".Trash-{}", uid)))
+            })
+        }
+
+        #[cfg(not(unix))]
+        {
+            let _ = path;
+            None
+        }
+    }
+
+    /// Root of this trash, holding `files/` and `info/`
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Directory holding the trashed files themselves
+    pub fn files_dir(&self) -> PathBuf {
+        self.root.join("files")
+    }
+
+    /// Directory holding the `.trashinfo` metadata entries
+    pub fn info_dir(&self) -> PathBuf {
+        self.root.join("info")
+    }
+
+    /// Move a path into the trash and return its new location
+    ///
+    /// A path on another filesystem goes to the trash of its own volume instead, created
+    /// (private to the user) if needed.
+    pub fn move_to_trash(&self, path: &Path) -> Result<PathBuf> {
+        let original = std::path::absolute(path)?;
+        match self.move_into(&original) {
+            Err(e) if is_cross_device(&e) => {
+                let parent = original.parent().unwrap_or(&original);
+                let volume = Self::for_volume_of(parent).with_context(|| {
+                    format!(
+                        "Cannot trash '{}': it is on another filesystem than the trash at '{}'",
+                        original.display(),
+                        self.root.display()
+                    )
+                })?;
+                volume.create_private().with_context(|| {
+                    format!(
+                        "Cannot trash '{}': it is on another filesystem than the trash at '{}', \
+                         and no trash could be created at '{}'",
+                        original.display(),
+                        self.root.display(),
+                        volume.root.display()
+                    )
+                })?;
+                volume.move_into(&original)
+            }
+            result => result,
+        }
+    }
+
+    /// Create this trash, readable only by its owner, unless it already exists
+    fn create_private(&self) -> Result<()> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::DirBuilderExt;
+
+            if fs::symlink_metadata(&self.root).is_ok_and(|metadata| metadata.is_dir()) {
+                return Ok(());
+            }
+            fs::DirBuilder::new().recursive(true).mode(0o700).create(&self.root)?;
+        }
+
+        #[cfg(not(unix))]
+        fs::create_dir_all(&self.root)?;
+
+        Ok(())
+    }
+
+    /// Rename an absolute path into this trash, writing its info entry first
+    fn move_into(&self, original: &Path) -> Result<PathBuf> {
+        let name = original
+            .file_name()
+            .context("Cannot trash a path without a file name")?;
+
+        fs::create_dir_all(self.files_dir())?;
+        fs::create_dir_all(self.info_dir())?;
+
+        // Reserve a unique name by creating the .trashinfo file exclusively
+        for attempt in 1u32.. {
+            let mut trash_name = name.to_os_string();
+            if attempt > 1 {
+                trash_name.push(format!(".{}", attempt));
+            }
+
+            let mut info_name = trash_name.clone();
+            info_name.push(".trashinfo");
+            let info_path = self.info_dir().join(info_name);
+            let mut info_file = match OpenOptions::new().write(true).create_new(true).open(&info_path) {
+                Ok(file) => file,
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e.into()),
+            };
+
+            let destination = self.files_dir().join(&trash_name);
+            if fs::symlink_metadata(&destination).is_ok() {
+                // Orphaned file without an info entry - release the reservation and try the next name
+                let _ = fs::remove_file(&info_path);
+                continue;
+            }
+
+            write!(
+                info_file,
+                "[Trash Info]\nPath={}\nDeletionDate={}\n",
+                encode_trash_path(original),
+                chrono::Local::now().format("%Y-%m-%dT%H:%M:%S")
+            )?;
+
+            if let Err(e) = fs::rename(original, &destination) {
+                let _ = fs::remove_file(&info_path);
+                return Err(e).with_context(|| {
+                    format!(
+                        "Failed to move '{}' to trash at '{}'",
+                        original.display(),
+                        self.root.display()
+                    )
+                });
+            }
+
+            return Ok(destination);
+        }
+
+        unreachable!("exhausted trash name candidates")
+    }
+
+    /// List everything in the trash that has a readable info entry and still exists
+    pub fn list(&self) -> Result<Vec<TrashedItem>> {
+        let entries = match fs::read_dir(self.info_dir()) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).context("Failed to read the trash"),
+        };
+
+        let mut items = Vec::new();
+        for entry in entries {
+            let info_path = entry?.path();
+            let Some(name) = info_path
+                .file_name()
+                .and_then(|name| os_str_bytes(name).strip_suffix(b".trashinfo").map(os_string_from_bytes))
+            else {
+                continue;
+            };
+            let Ok(info) = fs::read_to_string(&info_path) else {
+                continue;
+            };
+
+            let location = self.files_dir().join(name);
+            let original = info.lines().find_map(|line| line.strip_prefix("Path=")).map(decode_trash_path);
+            if let Some(original) = original
+                && fs::symlink_metadata(&location).is_ok()
+            {
+                items.push(TrashedItem {
+                    original,
+                    location,
+                    deleted_at: info
+                        .lines()
+                        .find_map(|line| line.strip_prefix("DeletionDate="))
+                        .map(str::to_string),
+                    info_path,
+                });
+            }
+        }
+
+        items.sort_by(|a, b| a.location.cmp(&b.location));
+        Ok(items)
+    }
+
+    /// Move an item back to its original location, which must not exist
+    pub fn restore(&self, item: &TrashedItem) -> Result<()> {
+        if fs::symlink_metadata(&item.original).is_ok() {
+            return Err(anyhow::anyhow!(
+                "Cannot restore '{}': something already exists there",
+                item.original.display()
+            ));
+        }
+
+        if let Some(parent) = item.original.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(&item.location, &item.original).with_context(|| {
+            format!("Failed to restore '{}' from '{}'", item.original.display(), item.location.display())
+        })?;
+        let _ = fs::remove_file(&item.info_path);
+
+        Ok(())
+    }
+}
+
+/// Whether an error is a rename failing because source and destination are on different filesystems
+fn is_cross_device(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<std::io::Error>()
+        .is_some_and(|e| e.kind() == ErrorKind::CrossesDevices)
+}
+
+/// Decode the percent-encoded `Path=` value of a `.trashinfo` file
+fn decode_trash_path(encoded: &str) -> PathBuf {
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|hex| std::str::from_utf8(hex).ok());
+        match (bytes[i], hex.and_then(|hex| u8::from_str_radix(hex, 16).ok())) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    PathBuf::from(os_string_from_bytes(&decoded))
+}
+
+/// Percent-encode a path for the `Path=` key of a `.trashinfo` file
+fn encode_trash_path(path: &Path) -> String {
+    let mut encoded = String::new();
+
+    for &byte in os_str_bytes(path.as_os_str()).iter() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    encoded
+}
+
+/// Bytes of a file name or path, exact on Unix where names need not be UTF-8
+#[cfg(unix)]
+fn os_str_bytes(name: &OsStr) -> std::borrow::Cow<'_, [u8]> {
+    use std::os::unix::ffi::OsStrExt;
+    std::borrow::Cow::Borrowed(name.as_bytes())
+}
+
+/// Bytes of a file name or path
+#[cfg(not(unix))]
+fn os_str_bytes(name: &OsStr) -> std::borrow::Cow<'_, [u8]> {
+    std::borrow::Cow::Owned(name.to_string_lossy().into_owned().into_bytes())
+}
+
+/// File name or path with the given bytes, the inverse of [`os_str_bytes`]
+#[cfg(unix)]
+fn os_string_from_bytes(bytes: &[u8]) -> OsString {
+    use std::os::unix::ffi::OsStrExt;
+    OsStr::from_bytes(bytes).to_os_string()
+}
+
+/// File name or path with the given bytes
+#[cfg(not(unix))]
+fn os_string_from_bytes(bytes: &[u8]) -> OsString {
+    OsString::from(String::from_utf8_lossy(bytes).into_owned())
+}
@@ -0,0 +1,244 @@
+//! Monorepo workspaces, whose node_modules only work together
+//!
+//! In a pnpm, npm or Yarn workspace the root's node_modules holds most dependencies and the
+//! members' node_modules link into it, so removing one without the others leaves a broken
+//! install. Workspaces are declared by `pnpm-workspace.yaml` or the `workspaces` field of the
+//! root `package.json`; their node_modules are deleted or kept as one unit.
+
+use glob::{MatchOptions, Pattern};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// A workspace root and the member packages it declares
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workspace {
+    /// Directory holding the workspace manifest
+    pub root: PathBuf,
+    /// Member package directories, sorted
+    pub members: Vec<PathBuf>,
+}
+
+/// A workspace whose node_modules are only partly among a set of targets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartialWorkspace {
+    /// Root of the workspace
+    pub root: PathBuf,
+    /// The workspace's node_modules that are among the targets
+    pub selected: Vec<PathBuf>,
+    /// The workspace's node_modules that are not
+    pub missing: Vec<PathBuf>,
+}
+
+impl Workspace {
+    /// Read the workspace declared in `dir`, if it has one
+    ///
+    /// `pnpm-workspace.yaml` wins over the `workspaces` field of `package.json`.
+    pub fn load(dir: &Path) -> Option<Self> {
+        let patterns = fs::read_to_string(dir.join("pnpm-workspace.yaml"))
+            .ok()
+            .map(|yaml| pnpm_packages(&yaml))
+            .or_else(|| {
+                let manifest = fs::read_to_string(dir.join("package.json")).ok()?;
+                package_json_workspaces(&manifest)
+            })?;
+
+        Some(Self {
+            root: dir.to_path_buf(),
+            members: find_members(dir, &patterns),
+        })
+    }
+
+    /// Whether `project` is the root or one of the members
+    pub fn contains(&self, project: &Path) -> bool {
+        project == self.root || self.members.binary_search_by(|member| member.as_path().cmp(project)).is_ok()
+    }
+
+    /// The node_modules of the root and the members that currently exist
+    pub fn node_modules(&self) -> Vec<PathBuf> {
+        std::iter::once(&self.root)
+            .chain(&self.members)
+            .map(|dir| dir.join("node_modules"))
+            .filter(|node_modules| node_modules.symlink_metadata().is_ok())
+            .collect()
+    }
+}
+
+/// Finds the workspace each project belongs to, reading every manifest once
+#[derive(Debug, Default)]
+pub struct WorkspaceIndex {
+    loaded: HashMap<PathBuf, Option<Workspace>>,
+}
+
+impl WorkspaceIndex {
+    /// Create an empty index
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The nearest workspace, at or above `project`, that includes it
+    pub fn workspace_of(&mut self, project: &Path) -> Option<&Workspace> {
+        let project = std::path::absolute(project).ok()?;
+
+        let root = project.ancestors().find(|dir| {
+            self.loaded
+                .entry(dir.to_path_buf())
+                .or_insert_with(|| Workspace::load(dir))
+                .as_ref()
+                .is_some_and(|workspace| workspace.contains(&project))
+        })?;
+        self.loaded.get(root)?.as_ref()
+    }
+}
+
+/// Workspaces of which some, but not all, node_modules are among `targets`
+///
+/// Paths in the result are absolute. Targets other than node_modules are not grouped.
+pub fn partial_workspaces(targets: &[PathBuf]) -> Vec<PartialWorkspace> {
+    let mut index = WorkspaceIndex::new();
+    let targets: HashSet<PathBuf> = targets
+        .iter()
+        .filter(|target| target.file_name().is_some_and(|name| name == "node_modules"))
+        .filter_map(|target| std::path::absolute(target).ok())
+        .collect();
+
+    let mut seen = HashSet::new();
+    let mut partial = Vec::new();
+    let mut projects: Vec<&Path> = targets.iter().filter_map(|target| target.parent()).collect();
+    projects.sort();
+
+    for project in projects {
+        let Some(workspace) = index.workspace_of(project) else {
+            continue;
+        };
+        if !seen.insert(workspace.root.clone()) {
+            continue;
+        }
+
+        let (selected, missing): (Vec<PathBuf>, Vec<PathBuf>) =
+            workspace.node_modules().into_iter().partition(|node_modules| targets.contains(node_modules));
+        if !missing.is_empty() {
+            partial.push(PartialWorkspace {
+                root: workspace.root.clone(),
+                selected,
+                missing,
+            });
+        }
+    }
+
+    partial
+}
+
+/// Member patterns from the `packages` list of a `pnpm-workspace.yaml`
+///
+/// Only the subset of YAML pnpm documents is understood: a block list of (optionally
+/// quoted) strings, or a flow list such as `packages: ['apps/*', 'packages/*']`.
+pub fn pnpm_packages(yaml: &str) -> Vec<String> {
+    let mut patterns = Vec::new();
+    let mut in_packages = false;
+
+    for line in yaml.lines() {
+        let line = strip_comment(line);
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if !line.starts_with(char::is_whitespace) && !line.starts_with('-') {
+            in_packages = false;
+            if let Some(value) = line.strip_prefix("packages:") {
+                let value = value.trim();
+                match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+                    Some(flow) => patterns.extend(flow.split(',').map(unquote).filter(|p| !p.is_empty())),
+                    None => in_packages = value.is_empty(),
+                }
+            }
+            continue;
+        }
+
+        if in_packages && let Some(item) = line.trim_start().strip_prefix('-') {
+            patterns.push(unquote(item));
+        }
+    }
+
+    patterns
+}
+
+/// Member patterns from the `workspaces` field of a `package.json`, if it has one
+///
+/// Both the array form and Yarn's `{ "packages": [...] }` form are accepted.
+pub fn package_json_workspaces(manifest: &str) -> Option<Vec<String>> {
+    let manifest: serde_json::Value = serde_json::from_str(manifest).ok()?;
+    let workspaces = manifest.get("workspaces")?;
+    let list = workspaces.get("packages").unwrap_or(workspaces).as_array()?;
+
+    Some(list.iter().filter_map(|pattern| pattern.as_str()).map(str::to_string).collect())
+}
+
+/// Directories below `root` that contain a `package.json` and match the member patterns
+///
+/// Patterns starting with `!` exclude members. node_modules and hidden directories are not
+/// searched, and the walk goes no deeper than the patterns can match.
+fn find_members(root: &Path, patterns: &[String]) -> Vec<PathBuf> {
+    let compile = |pattern: &str| Pattern::new(pattern.trim_start_matches("./").trim_end_matches('/')).ok();
+    let (excludes, includes): (Vec<&String>, Vec<&String>) = patterns.iter().partition(|p| p.starts_with('!'));
+    let includes: Vec<Pattern> = includes.iter().filter_map(|p| compile(p)).collect();
+    let excludes: Vec<Pattern> = excludes.iter().filter_map(|p| compile(&p[1..])).collect();
+    if includes.is_empty() {
+        return Vec::new();
+    }
+
+    let max_depth = if patterns.iter().any(|p| p.contains("**")) {
+        usize::MAX
+    } else {
+        patterns.iter().map(|p| p.trim_end_matches('/').split('/').count()).max().unwrap_or(0)
+    };
+    let options = MatchOptions {
+        require_literal_separator: true,
+        ..MatchOptions::new()
+    };
+
+    let mut members: Vec<PathBuf> = WalkDir::new(root)
+        .min_depth(1)
+        .max_depth(max_depth)
+        .into_iter()
+        .filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            entry.file_type().is_dir() && name != "node_modules" && !name.starts_with('.')
+        })
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().join("package.json").is_file())
+        .filter(|entry| {
+            let Ok(relative) = entry.path().strip_prefix(root) else {
+                return false;
+            };
+            let relative = relative.to_string_lossy();
+            includes.iter().any(|p| p.matches_with(&relative, options))
+                && !excludes.iter().any(|p| p.matches_with(&relative, options))
+        })
+        .map(|entry| entry.into_path())
+        .collect();
+
+    members.sort();
+    members
+}
+
+/// A YAML line without its trailing comment
+fn strip_comment(line: &str) -> &str {
+    match line.find(" #") {
+        Some(index) => &line[..index],
+        None if line.trim_start().starts_with('#') => "",
+        None => line,
+    }
+}
+
+/// A YAML scalar without surrounding whitespace and quotes
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    value
+        .strip_prefix('\'')
+        .and_then(|v| v.strip_suffix('\''))
+        .or_else(|| value.strip_prefix('"').and_then(|v| v.strip_suffix('"')))
+        .unwrap_or(value)
+        .to_string()
+}
@@ -0,0 +1,158 @@
+//! Unit tests for artifact module
+
+use anyhow::Result;
+use nuke_node_modules::artifact::{describe, ArtifactKind};
+use nuke_node_modules::scanner::{validate_targets, Scanner};
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_kind_requires_marker() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let target = temp_dir.path().join("crate/target");
+    fs::create_dir_all(&target)?;
+
+    assert!(!ArtifactKind::Target.matches(&target));
+    assert_eq!(ArtifactKind::from_path(&target), None);
+
+    fs::write(temp_dir.path().join("crate/Cargo.toml"), "[package]")?;
+    assert!(ArtifactKind::Target.matches(&target));
+    assert_eq!(ArtifactKind::from_path(&target), Some(ArtifactKind::Target));
+
+    Ok(())
+}
+
+#[test]
+fn test_node_modules_needs_no_marker() {
+    let path = std::path::Path::new("/anywhere/node_modules");
+    assert!(ArtifactKind::NodeModules.matches(path));
+    assert!(!ArtifactKind::Next.matches(path));
+}
+
+#[test]
+fn test_scanner_finds_enabled_kinds() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let base = temp_dir.path();
+
+    fs::create_dir_all(base.join("web/node_modules"))?;
+    fs::create_dir_all(base.join("web/.next/cache"))?;
+    fs::write(base.join("web/package.json"), "{}")?;
+
+    fs::create_dir_all(base.join("cli/target/debug"))?;
+    fs::write(base.join("cli/Cargo.toml"), "[package]")?;
+
+    // A plain "target" folder without Cargo.toml is not a build artifact
+    fs::create_dir_all(base.join("docs/target"))?;
+    // Artifacts inside a target must not be reported separately
+    fs::create_dir_all(base.join("cli/target/debug/build/node_modules"))?;
+
+    let scanner = Scanner::new(base, &[]).with_kinds(&[
+        ArtifactKind::NodeModules,
+        ArtifactKind::Next,
+        ArtifactKind::Target,
+    ]);
+    let targets = scanner.find_node_modules_dirs()?;
+
+    assert_eq!(
+        targets,
+        vec![
+            base.join("cli/target"),
+            base.join("web/.next"),
+            base.join("web/node_modules"),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_scanner_default_kind_is_node_modules() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let base = temp_dir.path();
+
+    fs::create_dir_all(base.join("app/node_modules"))?;
+    fs::create_dir_all(base.join("app/dist"))?;
+    fs::write(base.join("app/package.json"), "{}")?;
+
+    let targets = Scanner::new(base, &[]).with_kinds(&[]).find_node_modules_dirs()?;
+    assert_eq!(targets, vec![base.join("app/node_modules")]);
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_targets_per_kind() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let venv = temp_dir.path().join("py/.venv");
+    fs::create_dir_all(&venv)?;
+
+    assert!(validate_targets(std::slice::from_ref(&venv)).is_err());
+
+    fs::write(temp_dir.path().join("py/pyproject.toml"), "")?;
+    assert!(validate_targets(&[venv]).is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn test_describe_kinds() {
+    assert_eq!(describe(&[]), "node_modules");
+    assert_eq!(describe(&[ArtifactKind::NodeModules, ArtifactKind::Target]), "node_modules/target");
+}
+
+#[test]
+fn test_yarn_pnp_targets() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let base = temp_dir.path();
+
+    fs::create_dir_all(base.join("pnp/.yarn/cache"))?;
+    fs::write(base.join("pnp/.yarn/cache/react-npm-18.zip"), "zip")?;
+    fs::write(base.join("pnp/.pnp.cjs"), "// loader")?;
+    fs::write(base.join("pnp/yarn.lock"), "")?;
+    // Without a yarn.lock these are someone else's files
+    fs::create_dir_all(base.join("other/.yarn/cache"))?;
+    fs::write(base.join("other/.pnp.cjs"), "")?;
+
+    assert!(ArtifactKind::YarnCache.matches(&base.join("pnp/.yarn/cache")));
+    assert!(!ArtifactKind::YarnCache.matches(&base.join("other/.yarn/cache")));
+    assert_eq!(ArtifactKind::YarnCache.project_dir(&base.join("pnp/.yarn/cache")), Some(base.join("pnp").as_path()));
+
+    let scanner = Scanner::new(base, &[]).with_kinds(&[ArtifactKind::YarnCache, ArtifactKind::Pnp]);
+    let expected = vec![base.join("pnp/.pnp.cjs"), base.join("pnp/.yarn/cache")];
+    assert_eq!(scanner.find_node_modules_dirs()?, expected);
+
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(2).build()?;
+    assert_eq!(scanner.find_node_modules_dirs_parallel(&pool)?, expected);
+
+    // Not enabled by default
+    assert!(Scanner::new(base, &[]).find_node_modules_dirs()?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_cleanup_removes_pnp_file_and_cache() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let base = temp_dir.path();
+    fs::create_dir_all(base.join("app/.yarn/cache"))?;
+    fs::write(base.join("app/.yarn/cache/lodash.zip"), "zip")?;
+    fs::write(base.join("app/.yarn/releases"), "")?;
+    fs::write(base.join("app/.pnp.cjs"), "// loader")?;
+    fs::write(base.join("app/yarn.lock"), "")?;
+
+    let config = nuke_node_modules::Config {
+        kinds: vec![ArtifactKind::YarnCache, ArtifactKind::Pnp],
+        no_confirm: true,
+        quiet: true,
+        ..Default::default()
+    };
+    let stats = nuke_node_modules::cleanup_node_modules(base, &config)?;
+
+    assert_eq!(stats.directories_deleted, 2);
+    assert!(!base.join("app/.pnp.cjs").exists());
+    assert!(!base.join("app/.yarn/cache").exists());
+    assert!(base.join("app/.yarn/releases").exists());
+    assert!(base.join("app/yarn.lock").exists());
+
+    Ok(())
+}
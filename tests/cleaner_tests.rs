@@ -58,6 +58,81 @@ fn test_delete_multiple_directories() -> Result<()> {
     Ok(())
 }
 
+/// Test that no deletions start once the cancel flag is set
+#[test]
+fn test_cancelled_cleanup_leaves_targets() -> Result<()> {
+    use nuke_node_modules::report::TargetStatus;
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicBool;
+
+    let temp_dir = TempDir::new()?;
+    let targets = vec![
+        temp_dir.path().join("project1/node_modules"),
+        temp_dir.path().join("project2/node_modules"),
+    ];
+    for target in &targets {
+        common::create_test_directory_with_content(target, 2)?;
+    }
+
+    let cleaner = Cleaner::new(Some(2), false).with_cancel_flag(Some(Arc::new(AtomicBool::new(true))));
+    assert!(cleaner.is_cancelled());
+
+    let stats = cleaner.delete_directories(targets.clone())?;
+
+    for target in &targets {
+        assert!(target.exists());
+    }
+    assert_eq!(stats.directories_cancelled, 2);
+    assert_eq!(stats.directories_deleted, 0);
+    assert_eq!(stats.directories_failed, 0);
+    assert_eq!(stats.bytes_freed, 0);
+    assert!(stats.targets.iter().all(|t| t.status == TargetStatus::Cancelled));
+
+    Ok(())
+}
+
+/// Test deleting targets from a stream, with invalid entries recorded as failures
+#[test]
+fn test_delete_stream() -> Result<()> {
+    use nuke_node_modules::report::TargetStatus;
+
+    let temp_dir = TempDir::new()?;
+    let targets = vec![
+        temp_dir.path().join("project1/node_modules"),
+        temp_dir.path().join("project2/node_modules"),
+    ];
+    for target in &targets {
+        common::create_test_directory_with_content(target, 2)?;
+    }
+    let not_a_target = temp_dir.path().join("src");
+    fs::create_dir_all(&not_a_target)?;
+
+    let cleaner = Cleaner::new(Some(2), false);
+    let stream = targets.iter().cloned().chain([not_a_target.clone()]).map(Ok);
+    let stats = cleaner.delete_stream(stream)?;
+
+    for target in &targets {
+        assert!(!target.exists());
+    }
+    assert!(not_a_target.exists());
+    assert_eq!(stats.directories_found, 3);
+    assert_eq!(stats.directories_deleted, 2);
+    assert_eq!(stats.directories_failed, 1);
+    assert_eq!(stats.targets[2].status, TargetStatus::Failed);
+
+    Ok(())
+}
+
+/// Test that a scan error in the stream is returned
+#[test]
+fn test_delete_stream_scan_error() {
+    let cleaner = Cleaner::new(Some(1), false);
+    let stream = vec![Err(anyhow::anyhow!("walk failed"))].into_iter();
+
+    let error = cleaner.delete_stream(stream).unwrap_err();
+    assert!(format!("{:#}", error).contains("walk failed"));
+}
+
 #[test]
 fn test_invalid_path_safety() {
     let cleaner = Cleaner::new(Some(1), false);
@@ -67,6 +142,33 @@ fn test_invalid_path_safety() {
     assert!(result.is_err());
 }
 
+/// Test that without a root, a symlink is only deleted through when it stays in its project
+#[cfg(unix)]
+#[test]
+fn test_delete_through_symlink_without_root() -> Result<()> {
+    use std::os::unix::fs::symlink;
+
+    let temp_dir = TempDir::new()?;
+    let outside = temp_dir.path().join("store");
+    let inside = temp_dir.path().join("linked/.store");
+    fs::create_dir_all(&outside)?;
+    fs::create_dir_all(&inside)?;
+    fs::create_dir_all(temp_dir.path().join("app"))?;
+    symlink(&outside, temp_dir.path().join("app/node_modules"))?;
+    symlink(&inside, temp_dir.path().join("linked/node_modules"))?;
+
+    let stats = Cleaner::new(Some(1), false)
+        .with_delete_symlinked(true)
+        .delete_directories(vec![temp_dir.path().join("app/node_modules"), temp_dir.path().join("linked/node_modules")])?;
+
+    assert_eq!(stats.directories_deleted, 1);
+    assert_eq!(stats.directories_failed, 1);
+    assert!(outside.exists());
+    assert!(!inside.exists());
+
+    Ok(())
+}
+
 #[test]
 fn test_calculate_directory_size() -> Result<()> {
     let temp_dir = TempDir::new()?;
@@ -314,6 +416,7 @@ fn test_print_cleanup_summary() {
         directories_deleted: 4,
         directories_failed: 1,
         bytes_freed: 1024 * 1024, // 1 MB
+        ..Default::default()
     };
 
     // Function should not panic
@@ -332,6 +435,7 @@ fn test_print_cleanup_summary_zero_values() {
         directories_deleted: 0,
         directories_failed: 0,
         bytes_freed: 0,
+        ..Default::default()
     };
 
     // Function should not panic with zero values
@@ -350,6 +454,7 @@ fn test_print_cleanup_summary_large_values() {
         directories_deleted: 999,
         directories_failed: 1,
         bytes_freed: 1024 * 1024 * 1024 * 5, // 5 GB
+        ..Default::default()
     };
 
     // Function should not panic with large values
@@ -368,6 +473,7 @@ fn test_print_cleanup_summary_no_failures() {
         directories_deleted: 10,
         directories_failed: 0,
         bytes_freed: 512 * 1024, // 512 KB
+        ..Default::default()
     };
 
     // Function should not panic with no failures
@@ -386,8 +492,142 @@ fn test_print_cleanup_summary_no_bytes_freed() {
         directories_deleted: 3,
         directories_failed: 0,
         bytes_freed: 0, // Empty directories
+        ..Default::default()
     };
 
     // Function should not panic when no bytes are freed
     print_cleanup_summary(&stats);
-}
\ No newline at end of file
+}
+
+/// Test measuring target sizes and ages before deletion
+#[test]
+fn test_measure_targets() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+
+    let small = temp_dir.path().join("small/node_modules");
+    let large = temp_dir.path().join("large/node_modules");
+    let small_bytes = common::create_test_directory_with_content(&small, 1)?;
+    let large_bytes = common::create_test_directory_with_content(&large, 10)?;
+    common::write_file_aged(&temp_dir.path().join("large/package.json"), "{}", 10)?;
+
+    let cleaner = Cleaner::new(Some(2), false);
+    let infos = cleaner.measure_targets(&[small.clone(), large.clone()]);
+
+    assert_eq!(infos.len(), 2);
+    assert_eq!(infos[0].path, small);
+    assert_eq!(infos[0].apparent_bytes, small_bytes);
+    assert!(infos[0].bytes >= small_bytes);
+    assert!(infos[0].age.is_none());
+    assert_eq!(infos[1].apparent_bytes, large_bytes);
+    assert_eq!(infos[1].age.map(|a| a.as_secs() / 86400), Some(10));
+
+    // Measuring must not delete anything
+    assert!(small.exists());
+    assert!(large.exists());
+
+    Ok(())
+}
+
+/// Test sorting of measured targets by size, age and path
+#[test]
+fn test_sort_targets() {
+    use nuke_node_modules::cleaner::{sort_targets, SortOrder, TargetInfo};
+    use std::time::Duration;
+
+    let info = |path: &str, bytes: u64, age_days: Option<u64>| TargetInfo {
+        path: PathBuf::from(path),
+        bytes,
+        apparent_bytes: bytes,
+        age: age_days.map(|d| Duration::from_secs(d * 86400)),
+    };
+    let mut targets = vec![
+        info("/b/node_modules", 10, Some(5)),
+        info("/a/node_modules", 30, None),
+        info("/c/node_modules", 20, Some(50)),
+    ];
+    let paths = |targets: &[TargetInfo]| -> Vec<String> {
+        targets.iter().map(|t| t.path.display().to_string()).collect()
+    };
+
+    sort_targets(&mut targets, SortOrder::Size);
+    assert_eq!(paths(&targets), ["/a/node_modules", "/c/node_modules", "/b/node_modules"]);
+
+    sort_targets(&mut targets, SortOrder::Age);
+    assert_eq!(paths(&targets), ["/c/node_modules", "/b/node_modules", "/a/node_modules"]);
+
+    sort_targets(&mut targets, SortOrder::Path);
+    assert_eq!(paths(&targets), ["/a/node_modules", "/b/node_modules", "/c/node_modules"]);
+}
+
+/// Test that hardlinked files are only counted once across targets
+#[cfg(unix)]
+#[test]
+fn test_disk_usage_counts_hardlinks_once() -> Result<()> {
+    use nuke_node_modules::cleaner::{calculate_disk_usage, InodeTracker};
+
+    let temp_dir = TempDir::new()?;
+    let first = temp_dir.path().join("app1/node_modules");
+    let second = temp_dir.path().join("app2/node_modules");
+    fs::create_dir_all(&first)?;
+    fs::create_dir_all(&second)?;
+
+    // pnpm-style: both projects hardlink the same package file
+    fs::write(first.join("lodash.js"), "x".repeat(10_000))?;
+    fs::hard_link(first.join("lodash.js"), second.join("lodash.js"))?;
+
+    let inodes = InodeTracker::new();
+    let first_usage = calculate_disk_usage(&first, &inodes)?;
+    let second_usage = calculate_disk_usage(&second, &inodes)?;
+
+    assert_eq!(first_usage.apparent, 10_000);
+    assert!(first_usage.allocated >= 10_000);
+    assert_eq!(second_usage.apparent, 0);
+
+    // A fresh tracker counts the file again
+    let fresh = calculate_disk_usage(&second, &InodeTracker::new())?;
+    assert_eq!(fresh.apparent, 10_000);
+
+    Ok(())
+}
+
+/// Test that sparse files report fewer allocated than apparent bytes
+#[cfg(unix)]
+#[test]
+fn test_disk_usage_sparse_file() -> Result<()> {
+    use nuke_node_modules::cleaner::{calculate_disk_usage, InodeTracker};
+
+    let temp_dir = TempDir::new()?;
+    let node_modules = temp_dir.path().join("node_modules");
+    fs::create_dir_all(&node_modules)?;
+
+    let file = fs::File::create(node_modules.join("sparse.bin"))?;
+    file.set_len(64 * 1024 * 1024)?;
+
+    let usage = calculate_disk_usage(&node_modules, &InodeTracker::new())?;
+    assert_eq!(usage.apparent, 64 * 1024 * 1024);
+    assert!(usage.allocated < usage.apparent);
+
+    Ok(())
+}
+
+/// Test that deletion stats dedupe hardlinks across targets
+#[cfg(unix)]
+#[test]
+fn test_delete_directories_dedupes_hardlinks() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let first = temp_dir.path().join("app1/node_modules");
+    let second = temp_dir.path().join("app2/node_modules");
+    fs::create_dir_all(&first)?;
+    fs::create_dir_all(&second)?;
+    fs::write(first.join("react.js"), "x".repeat(8_000))?;
+    fs::hard_link(first.join("react.js"), second.join("react.js"))?;
+
+    let cleaner = Cleaner::new(Some(2), false);
+    let stats = cleaner.delete_directories(vec![first, second])?;
+
+    assert_eq!(stats.directories_deleted, 2);
+    assert_eq!(stats.bytes_freed_apparent, 8_000);
+    assert!(stats.bytes_freed >= 8_000);
+
+    Ok(())
+}
@@ -58,6 +58,288 @@ fn test_config_conversion() {
     assert!(config.dry_run);
     assert!(config.quiet);
     assert!(!config.no_confirm); // Default
+    assert!(!config.trash); // Default
+}
+
+#[test]
+fn test_older_than_flag() {
+    let cli = Cli::parse_from(["nuke-node-modules", "--older-than", "30d"]);
+    let expected = std::time::Duration::from_secs(30 * 24 * 60 * 60);
+    assert_eq!(cli.older_than, Some(expected));
+    assert_eq!(cli.to_config().older_than, Some(expected));
+
+    assert!(Cli::try_parse_from(["nuke-node-modules", "--older-than", "soon"]).is_err());
+}
+
+#[test]
+fn test_interactive_flag() {
+    let cli = Cli::parse_from(["nuke-node-modules", "--interactive"]);
+    assert!(cli.interactive);
+    assert!(cli.to_config().interactive);
+
+    // Interactive selection makes no sense without prompting
+    assert!(Cli::try_parse_from(["nuke-node-modules", "-i", "--no-confirm"]).is_err());
+    assert!(Cli::try_parse_from(["nuke-node-modules", "-i", "--quiet"]).is_err());
+}
+
+#[test]
+fn test_cli_overrides_file_config() {
+    use nuke_node_modules::config_file::FileConfig;
+
+    let mut config = nuke_node_modules::Config::default();
+    FileConfig {
+        exclude: vec!["**/vendor/**".to_string()],
+        threads: Some(2),
+        older_than: Some("30d".to_string()),
+        ..Default::default()
+    }
+    .apply_to(&mut config)
+    .unwrap();
+
+    let cli = Cli::parse_from(["nuke-node-modules", "--threads", "8", "--exclude", "**/build/**"]);
+    cli.apply_to(&mut config);
+
+    assert_eq!(config.threads, Some(8));
+    assert_eq!(config.exclude_patterns, vec!["**/vendor/**", "**/build/**"]);
+    assert_eq!(config.older_than, Some(std::time::Duration::from_secs(30 * 24 * 60 * 60)));
+}
+
+#[test]
+fn test_no_flags_turn_off_file_config() {
+    use nuke_node_modules::config_file::FileConfig;
+
+    let file_config = FileConfig {
+        trash: Some(true),
+        skip_dirty: Some(true),
+        ..Default::default()
+    };
+
+    let mut config = nuke_node_modules::Config::default();
+    file_config.apply_to(&mut config).unwrap();
+    Cli::parse_from(["nuke-node-modules"]).apply_to(&mut config);
+    assert!(config.trash);
+    assert!(config.skip_dirty);
+
+    Cli::parse_from(["nuke-node-modules", "--no-trash", "--no-skip-dirty"]).apply_to(&mut config);
+    assert!(!config.trash);
+    assert!(!config.skip_dirty);
+
+    // The last of a flag and its negation wins
+    let cli = Cli::parse_from(["nuke-node-modules", "--no-trash", "--trash"]);
+    assert!(cli.trash && !cli.no_trash);
+}
+
+#[test]
+fn test_no_config_ignores_files() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    fs::write(
+        temp_dir.path().join(nuke_node_modules::config_file::TREE_CONFIG_FILE),
+        "threads = 3\n",
+    )?;
+
+    let cli = Cli::parse_from(["nuke-node-modules", "--no-config"]);
+    assert_eq!(cli.load_config(temp_dir.path())?.threads, None);
+
+    Ok(())
+}
+
+#[test]
+fn test_kind_flag() {
+    use nuke_node_modules::artifact::ArtifactKind;
+
+    let cli = Cli::parse_from(["nuke-node-modules", "--kind", "node-modules", "--kind", "target"]);
+    assert_eq!(cli.kinds, vec![ArtifactKind::NodeModules, ArtifactKind::Target]);
+    assert_eq!(cli.to_config().kinds, cli.kinds);
+
+    assert!(Cli::try_parse_from(["nuke-node-modules", "--kind", "vendor"]).is_err());
+}
+
+#[test]
+fn test_sort_and_top_flags() {
+    use nuke_node_modules::cleaner::SortOrder;
+
+    let cli = Cli::parse_from(["nuke-node-modules", "--sort", "size", "--top", "5"]);
+    let config = cli.to_config();
+    assert_eq!(config.sort, SortOrder::Size);
+    assert_eq!(config.top, Some(5));
+
+    let cli = Cli::parse_from(["nuke-node-modules"]);
+    assert_eq!(cli.sort, SortOrder::Path);
+    assert!(cli.top.is_none());
+}
+
+#[test]
+fn test_size_limit_flags() {
+    use nuke_node_modules::config_file::FileConfig;
+    use nuke_node_modules::format_bytes;
+
+    let cli = Cli::parse_from(["nuke-node-modules", "--min-size", "100M", "--max-size", "1.5 GB"]);
+    let config = cli.to_config();
+    assert_eq!(config.min_size, Some(100 * 1024 * 1024));
+    assert_eq!(config.max_size, Some(1536 * 1024 * 1024));
+
+    // What format_bytes prints is accepted back
+    let cli = Cli::parse_from(["nuke-node-modules", "scan", "--min-size", &format_bytes(1536 * 1024)]);
+    assert_eq!(cli.to_config().min_size, Some(1536 * 1024));
+
+    // Flags override the config file
+    let mut config = nuke_node_modules::Config::default();
+    FileConfig {
+        min_size: Some("1G".to_string()),
+        max_size: Some("10G".to_string()),
+        ..Default::default()
+    }
+    .apply_to(&mut config)
+    .unwrap();
+    Cli::parse_from(["nuke-node-modules", "--min-size", "10M"]).apply_to(&mut config);
+    assert_eq!(config.min_size, Some(10 * 1024 * 1024));
+    assert_eq!(config.max_size, Some(10 * 1024 * 1024 * 1024));
+
+    assert!(Cli::try_parse_from(["nuke-node-modules", "--min-size", "big"]).is_err());
+}
+
+#[test]
+fn test_reclaim_flags() {
+    use nuke_node_modules::reclaim::ReclaimGoal;
+
+    let cli = Cli::parse_from(["nuke-node-modules", "--reclaim", "20G"]);
+    assert_eq!(cli.to_config().reclaim, Some(ReclaimGoal::Bytes(20 * 1024 * 1024 * 1024)));
+
+    let cli = Cli::parse_from(["nuke-node-modules", "--until-free", "512M"]);
+    assert_eq!(cli.to_config().reclaim, Some(ReclaimGoal::UntilFree(512 * 1024 * 1024)));
+
+    assert!(Cli::try_parse_from(["nuke-node-modules", "--reclaim", "1G", "--until-free", "1G"]).is_err());
+    assert!(Cli::try_parse_from(["nuke-node-modules", "--reclaim", "1G", "--top", "3"]).is_err());
+    assert!(Cli::try_parse_from(["nuke-node-modules", "--reclaim", "lots"]).is_err());
+    assert!(Cli::try_parse_from(["nuke-node-modules", "--reclaim", "1G", "--trash"]).is_err());
+    assert!(Cli::try_parse_from(["nuke-node-modules", "--until-free", "1G", "--trash"]).is_err());
+}
+
+#[test]
+fn test_skip_dirty_flag() {
+    let cli = Cli::parse_from(["nuke-node-modules", "--skip-dirty"]);
+    assert!(cli.skip_dirty);
+    assert!(cli.to_config().skip_dirty);
+
+    let cli = Cli::parse_from(["nuke-node-modules"]);
+    assert!(!cli.to_config().skip_dirty);
+}
+
+#[test]
+fn test_pipeline_flag() {
+    let cli = Cli::parse_from(["nuke-node-modules", "--pipeline", "--no-confirm"]);
+    assert!(cli.to_config().pipeline);
+
+    assert!(Cli::try_parse_from(["nuke-node-modules", "--pipeline"]).is_err());
+    assert!(Cli::try_parse_from(["nuke-node-modules", "--pipeline", "--no-confirm", "--dry-run"]).is_err());
+}
+
+#[test]
+fn test_symlink_and_mount_flags() {
+    let config = Cli::parse_from(["nuke-node-modules"]).to_config();
+    assert!(!config.follow_symlinks && !config.one_file_system && !config.delete_symlinked);
+
+    let cli = Cli::parse_from([
+        "nuke-node-modules",
+        "--follow-symlinks",
+        "--one-file-system",
+        "--delete-symlinked-node-modules",
+    ]);
+    let config = cli.to_config();
+    assert!(config.follow_symlinks);
+    assert!(config.one_file_system);
+    assert!(config.delete_symlinked);
+}
+
+#[test]
+fn test_safety_flags() {
+    let config = Cli::parse_from(["nuke-node-modules"]).to_config();
+    assert!(!config.safety.allow_dangerous_roots);
+    assert_eq!(config.safety.confirm_above_count, Some(100));
+    assert_eq!(config.safety.confirm_above_bytes, None);
+
+    let cli = Cli::parse_from([
+        "nuke-node-modules",
+        "--i-know-what-im-doing",
+        "--confirm-above-count",
+        "20",
+        "--confirm-above-size",
+        "10G",
+    ]);
+    let config = cli.to_config();
+    assert!(config.safety.allow_dangerous_roots);
+    assert_eq!(config.safety.confirm_above_count, Some(20));
+    assert_eq!(config.safety.confirm_above_bytes, Some(10 * 1024 * 1024 * 1024));
+}
+
+#[test]
+fn test_journal_flags_and_history_command() {
+    use nuke_node_modules::cli::Command;
+
+    let cli = Cli::parse_from(["nuke-node-modules", "--no-journal"]);
+    assert_eq!(cli.to_config().journal, None);
+
+    let cli = Cli::parse_from(["nuke-node-modules", "--resume", "/projects"]);
+    assert!(cli.to_config().resume);
+    assert!(Cli::try_parse_from(["nuke-node-modules", "--resume", "--no-journal"]).is_err());
+
+    let cli = Cli::parse_from(["nuke-node-modules", "history", "--limit", "5"]);
+    assert_eq!(cli.command, Some(Command::History { limit: Some(5) }));
+}
+
+#[test]
+fn test_subcommands_and_bare_invocation() {
+    use nuke_node_modules::cli::Command;
+
+    let cli = Cli::parse_from(["nuke-node-modules", "/projects"]);
+    assert_eq!(cli.command, None);
+    assert_eq!(cli.get_root_path(), PathBuf::from("/projects"));
+
+    let cli = Cli::parse_from(["nuke-node-modules", "scan", "--kind", "target", "/projects"]);
+    assert_eq!(cli.command, Some(Command::Scan { path: Some(PathBuf::from("/projects")) }));
+    assert_eq!(cli.get_root_path(), PathBuf::from("/projects"));
+    assert_eq!(cli.kinds.len(), 1);
+
+    let cli = Cli::parse_from(["nuke-node-modules", "clean", "-y", "/projects"]);
+    assert!(matches!(cli.command, Some(Command::Clean { .. })));
+    assert!(cli.no_confirm);
+
+    let cli = Cli::parse_from(["nuke-node-modules", "report", "--format", "json"]);
+    assert!(matches!(cli.command, Some(Command::Report { path: None })));
+    assert!(cli.is_quiet());
+
+    let cli = Cli::parse_from(["nuke-node-modules", "restore", "--dry-run", "/projects"]);
+    assert!(matches!(cli.command, Some(Command::Restore { .. })));
+    assert!(cli.to_config().dry_run);
+}
+
+#[test]
+fn test_subcommand_names_shadow_bare_paths() {
+    use nuke_node_modules::cli::Command;
+
+    // A directory named like a command is taken as that command...
+    let cli = Cli::parse_from(["nuke-node-modules", "report"]);
+    assert_eq!(cli.command, Some(Command::Report { path: None }));
+    assert_eq!(cli.get_root_path(), std::env::current_dir().unwrap());
+
+    // ...unless it is written as a path or given after `--`
+    let cli = Cli::parse_from(["nuke-node-modules", "./report"]);
+    assert_eq!(cli.command, None);
+    assert_eq!(cli.get_root_path(), PathBuf::from("./report"));
+
+    let cli = Cli::parse_from(["nuke-node-modules", "--", "history"]);
+    assert_eq!(cli.command, None);
+    assert_eq!(cli.get_root_path(), PathBuf::from("history"));
+
+    let cli = Cli::parse_from(["nuke-node-modules", "clean", "scan"]);
+    assert_eq!(cli.command, Some(Command::Clean { path: Some(PathBuf::from("scan")) }));
+}
+
+#[test]
+fn test_trash_flag() {
+    let cli = Cli::parse_from(["nuke-node-modules", "--trash"]);
+    assert!(cli.trash);
+    assert!(cli.to_config().trash);
 }
 
 #[test]
@@ -99,7 +381,7 @@ fn test_print_scan_info_default_threads() {
     let path = PathBuf::from(".");
 
     // Test the scan info printing (no threads specified)
-    cli.print_scan_info(&path);
+    cli.print_scan_info(&path, &cli.to_config());
     // This tests the auto-detected thread path
 }
 
@@ -109,7 +391,7 @@ fn test_print_scan_info_custom_threads() {
     let path = PathBuf::from("/test/path");
 
     // Test with custom thread count
-    cli.print_scan_info(&path);
+    cli.print_scan_info(&path, &cli.to_config());
     // This tests the custom thread count path
 }
 
@@ -119,7 +401,7 @@ fn test_print_scan_info_quiet_mode() {
     let path = PathBuf::from(".");
 
     // In quiet mode, should do nothing
-    cli.print_scan_info(&path);
+    cli.print_scan_info(&path, &cli.to_config());
     // This tests the quiet return path
 }
 
@@ -83,3 +83,16 @@ pub fn create_lib_test_structure(temp_dir: &TempDir) -> Result<()> {
     Ok(())
 }
 
+
+/// Write a file and backdate its modification time by the given number of days
+pub fn write_file_aged(path: &Path, contents: &str, days_old: u64) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)?;
+
+    let modified = std::time::SystemTime::now() - std::time::Duration::from_secs(days_old * 24 * 60 * 60);
+    fs::File::options().write(true).open(path)?.set_modified(modified)?;
+
+    Ok(())
+}
@@ -0,0 +1,124 @@
+//! Unit tests for config_file module
+
+use anyhow::Result;
+use nuke_node_modules::config_file::{find_tree_config, load_config, FileConfig, TREE_CONFIG_FILE};
+use nuke_node_modules::Config;
+use std::fs;
+use std::time::Duration;
+use tempfile::TempDir;
+
+#[test]
+fn test_load_file_config() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let path = temp_dir.path().join("config.toml");
+    fs::write(
+        &path,
+        r#"
+exclude = ["**/vendor/**"]
+threads = 4
+older_than = "30d"
+min_size = "100M"
+trash = true
+"#,
+    )?;
+
+    let file_config = FileConfig::load(&path)?;
+    assert_eq!(file_config.exclude, vec!["**/vendor/**"]);
+    assert_eq!(file_config.threads, Some(4));
+    assert_eq!(file_config.trash, Some(true));
+
+    let mut config = Config::default();
+    file_config.apply_to(&mut config)?;
+    assert_eq!(config.exclude_patterns, vec!["**/vendor/**"]);
+    assert_eq!(config.threads, Some(4));
+    assert_eq!(config.older_than, Some(Duration::from_secs(30 * 24 * 60 * 60)));
+    assert_eq!(config.min_size, Some(100 * 1024 * 1024));
+    assert_eq!(config.max_size, None);
+    assert!(config.trash);
+
+    Ok(())
+}
+
+#[test]
+fn test_unknown_keys_are_rejected() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let path = temp_dir.path().join("config.toml");
+    fs::write(&path, "thraeds = 4\n")?;
+
+    assert!(FileConfig::load(&path).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_invalid_values_are_rejected() {
+    let file_config = FileConfig {
+        older_than: Some("whenever".to_string()),
+        ..Default::default()
+    };
+
+    let mut config = Config::default();
+    assert!(file_config.apply_to(&mut config).is_err());
+}
+
+#[test]
+fn test_merge_later_file_wins() {
+    let user = FileConfig {
+        exclude: vec!["**/vendor/**".to_string()],
+        threads: Some(2),
+        trash: Some(true),
+        ..Default::default()
+    };
+    let tree = FileConfig {
+        exclude: vec!["**/build/**".to_string(), "**/vendor/**".to_string()],
+        threads: Some(8),
+        older_than: Some("2w".to_string()),
+        ..Default::default()
+    };
+
+    let merged = user.merge(tree);
+    assert_eq!(merged.exclude, vec!["**/vendor/**", "**/build/**"]);
+    assert_eq!(merged.threads, Some(8));
+    assert_eq!(merged.older_than.as_deref(), Some("2w"));
+    assert_eq!(merged.trash, Some(true));
+}
+
+#[test]
+fn test_find_tree_config_in_ancestors() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let nested = temp_dir.path().join("workspace/projects/app");
+    fs::create_dir_all(&nested)?;
+    assert_eq!(find_tree_config(&nested), None);
+
+    let config_path = temp_dir.path().join("workspace").join(TREE_CONFIG_FILE);
+    fs::write(&config_path, "threads = 3\n")?;
+
+    assert_eq!(find_tree_config(&nested), Some(config_path));
+    assert_eq!(load_config(&nested)?.threads, Some(3));
+
+    Ok(())
+}
+
+#[test]
+fn test_print_config_round_trip() -> Result<()> {
+    let config = Config {
+        exclude_patterns: vec!["**/build/**".to_string()],
+        threads: Some(6),
+        older_than: Some(Duration::from_secs(14 * 24 * 60 * 60)),
+        min_size: Some(100 * 1024 * 1024),
+        ..Default::default()
+    };
+
+    let rendered = FileConfig::from_config(&config).to_toml()?;
+    assert!(rendered.contains("older_than = \"2w\""));
+    assert!(rendered.contains("min_size = \"100.0 MB\""));
+
+    let mut reparsed = Config::default();
+    toml::from_str::<FileConfig>(&rendered)?.apply_to(&mut reparsed)?;
+    assert_eq!(reparsed.exclude_patterns, config.exclude_patterns);
+    assert_eq!(reparsed.threads, config.threads);
+    assert_eq!(reparsed.older_than, config.older_than);
+    assert_eq!(reparsed.min_size, config.min_size);
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+//! Unit tests for error module
+
+use anyhow::{Context, Result};
+use nuke_node_modules::cleaner::Cleaner;
+use nuke_node_modules::report::TargetStatus;
+use nuke_node_modules::safety::SafetyPolicy;
+use nuke_node_modules::scanner::validate_targets;
+use nuke_node_modules::NukeError;
+use std::io;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+#[test]
+fn test_from_io_classifies_by_kind() {
+    let path = Path::new("/p/node_modules");
+    let classify = |kind| NukeError::from_io(path, io::Error::from(kind));
+
+    assert!(matches!(classify(io::ErrorKind::PermissionDenied), NukeError::PermissionDenied { .. }));
+    assert!(matches!(classify(io::ErrorKind::NotFound), NukeError::NotFound { .. }));
+    assert!(matches!(classify(io::ErrorKind::ResourceBusy), NukeError::Busy { .. }));
+    assert!(matches!(classify(io::ErrorKind::Other), NukeError::Io { .. }));
+    assert_eq!(classify(io::ErrorKind::NotFound).path(), path);
+}
+
+#[test]
+fn test_from_anyhow_keeps_kind_and_context() {
+    let error = Err::<(), _>(io::Error::from(io::ErrorKind::PermissionDenied))
+        .context("Failed to move into the trash")
+        .unwrap_err();
+
+    let nuke_error = NukeError::from_anyhow(Path::new("/p/node_modules"), &error);
+    assert!(matches!(nuke_error, NukeError::PermissionDenied { .. }));
+    assert!(nuke_error.to_string().contains("Failed to move into the trash"));
+}
+
+#[test]
+fn test_partially_deleted_wraps_cause() {
+    let path = Path::new("/p/node_modules");
+    let error = NukeError::from_io(path, io::Error::from(io::ErrorKind::PermissionDenied)).partially_deleted();
+
+    match &error {
+        NukeError::PartiallyDeleted { path: partial, source } => {
+            assert_eq!(partial, path);
+            assert!(matches!(**source, NukeError::PermissionDenied { .. }));
+        }
+        other => panic!("expected PartiallyDeleted, got {:?}", other),
+    }
+    assert!(error.to_string().contains("only partially deleted"));
+}
+
+#[test]
+fn test_safety_checks_return_safety_violations() {
+    let not_a_target = PathBuf::from("/p/src");
+    match validate_targets(std::slice::from_ref(&not_a_target)) {
+        Err(NukeError::SafetyViolation { path, .. }) => assert_eq!(path, not_a_target),
+        other => panic!("expected a SafetyViolation, got {:?}", other),
+    }
+
+    let error = SafetyPolicy::default().check_root(Path::new("/")).unwrap_err();
+    assert!(matches!(error, NukeError::SafetyViolation { .. }));
+    assert!(error.to_string().starts_with("Safety check failed: refusing to clean '/'"));
+}
+
+#[test]
+fn test_failed_targets_carry_typed_errors() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let present = temp_dir.path().join("a/node_modules");
+    let missing = temp_dir.path().join("b/node_modules");
+    std::fs::create_dir_all(&present)?;
+
+    let stats = Cleaner::new(Some(1), false).delete_directories(vec![present, missing.clone()])?;
+
+    assert_eq!(stats.directories_deleted, 1);
+    assert_eq!(stats.directories_failed, 1);
+    assert_eq!(stats.targets.len(), 2);
+    assert_eq!(stats.targets[1].status, TargetStatus::Failed);
+
+    let errors: Vec<&NukeError> = stats.errors().collect();
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0], NukeError::NotFound { path } if *path == missing));
+
+    Ok(())
+}
@@ -0,0 +1,257 @@
+//! Unit tests for git module
+
+use anyhow::Result;
+use nuke_node_modules::git::{Index, Repository};
+use nuke_node_modules::scanner::Scanner;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tempfile::TempDir;
+
+mod common;
+
+/// Encode a version 2 index entry with the given path and size
+fn v2_entry(path: &str, size: u32) -> Vec<u8> {
+    let mut entry = Vec::new();
+    for field in [0, 0, 1_700_000_000, 0, 0, 0, 0o100644, 0, 0, size] {
+        entry.extend_from_slice(&u32::to_be_bytes(field));
+    }
+    entry.extend_from_slice(&[0; 20]);
+    entry.extend_from_slice(&(path.len() as u16).to_be_bytes());
+    entry.extend_from_slice(path.as_bytes());
+    entry.push(0);
+    while entry.len() % 8 != 0 {
+        entry.push(0);
+    }
+    entry
+}
+
+/// Encode an index file header
+fn header(version: u32, count: u32) -> Vec<u8> {
+    let mut bytes = b"DIRC".to_vec();
+    bytes.extend_from_slice(&version.to_be_bytes());
+    bytes.extend_from_slice(&count.to_be_bytes());
+    bytes
+}
+
+/// Run git in `dir`, returning false when git is not installed
+fn git(dir: &Path, args: &[&str]) -> Result<bool> {
+    let output = match Command::new("git")
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .output()
+    {
+        Ok(output) => output,
+        Err(_) => return Ok(false),
+    };
+
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    Ok(true)
+}
+
+/// Create a committed project with an ignored node_modules, returning false without git
+fn create_repo(dir: &Path) -> Result<bool> {
+    fs::create_dir_all(dir)?;
+    if !git(dir, &["init", "-q"])? {
+        return Ok(false);
+    }
+
+    fs::write(dir.join("package.json"), "{}")?;
+    fs::write(dir.join(".gitignore"), "node_modules\n")?;
+    common::create_test_directory_with_content(&dir.join("node_modules"), 2)?;
+    git(dir, &["add", "."])?;
+    git(dir, &["commit", "-q", "-m", "initial"])?;
+
+    Ok(true)
+}
+
+fn scan(root: &Path, skip_dirty: bool) -> Result<Vec<PathBuf>> {
+    Scanner::new(root, &[]).with_skip_dirty(skip_dirty).find_node_modules_dirs()
+}
+
+/// Test parsing a version 2 index
+#[test]
+fn test_parse_v2_index() -> Result<()> {
+    let mut bytes = header(2, 2);
+    bytes.extend(v2_entry("package.json", 2));
+    bytes.extend(v2_entry("vendor/node_modules/left-pad/index.js", 120));
+
+    let index = Index::parse(&bytes, 20)?;
+
+    assert_eq!(index.entries().len(), 2);
+    assert_eq!(index.entries()[1].size, 120);
+    assert!(index.tracks(Path::new("vendor/node_modules")));
+    assert!(index.tracks(Path::new("package.json")));
+    assert!(!index.tracks(Path::new("node_modules")));
+    assert!(!index.tracks(Path::new("vendor/node")));
+
+    Ok(())
+}
+
+/// Test parsing a version 4 index with prefix-compressed paths
+#[test]
+fn test_parse_v4_index() -> Result<()> {
+    let mut bytes = header(4, 2);
+    for (strip, suffix) in [(0u8, "app/node_modules/a.js"), (4, "b.js")] {
+        for field in [0u32; 10] {
+            bytes.extend_from_slice(&field.to_be_bytes());
+        }
+        bytes.extend_from_slice(&[0; 20]);
+        bytes.extend_from_slice(&0u16.to_be_bytes());
+        bytes.push(strip);
+        bytes.extend_from_slice(suffix.as_bytes());
+        bytes.push(0);
+    }
+
+    let index = Index::parse(&bytes, 20)?;
+
+    assert_eq!(index.entries()[1].path, b"app/node_modules/b.js");
+    assert!(index.tracks(Path::new("app/node_modules")));
+
+    Ok(())
+}
+
+/// Test that malformed index files are rejected
+#[test]
+fn test_parse_invalid_index() {
+    assert!(Index::parse(b"NOPE", 20).is_err());
+    assert!(Index::parse(&header(9, 0), 20).is_err());
+    assert!(Index::parse(&header(2, 1), 20).is_err());
+    // A corrupt entry count must fail on the missing entries, not on allocating them
+    assert!(Index::parse(&header(2, u32::MAX), 20).is_err());
+}
+
+/// Test finding the repository that contains a path
+#[test]
+fn test_discover_repository() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let project = temp_dir.path().join("project");
+    if !create_repo(&project)? {
+        return Ok(());
+    }
+
+    let repo = Repository::discover(&project.join("node_modules")).expect("repository");
+    assert_eq!(repo.work_tree(), project);
+    assert_eq!(repo.git_dir(), project.join(".git"));
+    assert!(Repository::discover(temp_dir.path()).is_none());
+
+    Ok(())
+}
+
+/// Test that node_modules committed to git are never reported
+#[test]
+fn test_tracked_node_modules_are_skipped() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let project = temp_dir.path().join("vendored");
+    if !create_repo(&project)? {
+        return Ok(());
+    }
+    common::create_test_directory_with_content(&project.join("lib/node_modules"), 1)?;
+    git(&project, &["add", "-f", "lib/node_modules"])?;
+    git(&project, &["commit", "-q", "-m", "vendor"])?;
+
+    let targets = scan(temp_dir.path(), false)?;
+
+    assert_eq!(targets, vec![project.join("node_modules")]);
+
+    Ok(())
+}
+
+/// Test that --skip-dirty keeps projects with modified or deleted tracked files
+#[test]
+fn test_skip_dirty_repositories() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let clean = temp_dir.path().join("clean");
+    let dirty = temp_dir.path().join("dirty");
+    if !create_repo(&clean)? || !create_repo(&dirty)? {
+        return Ok(());
+    }
+    fs::write(dirty.join("package.json"), r#"{"name": "patched"}"#)?;
+
+    assert_eq!(scan(temp_dir.path(), false)?.len(), 2);
+    assert_eq!(scan(temp_dir.path(), true)?, vec![clean.join("node_modules")]);
+
+    fs::write(dirty.join("package.json"), "{}")?;
+    git(&dirty, &["add", "package.json"])?;
+    fs::remove_file(dirty.join(".gitignore"))?;
+    assert_eq!(scan(temp_dir.path(), true)?, vec![clean.join("node_modules")]);
+
+    Ok(())
+}
+
+/// Test that --skip-dirty keeps projects with untracked files, but not ignored files or artifacts
+#[test]
+fn test_skip_dirty_untracked_files() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let project = temp_dir.path().join("project");
+    if !create_repo(&project)? {
+        return Ok(());
+    }
+    fs::write(project.join(".gitignore"), "node_modules\n*.log\n")?;
+    git(&project, &["commit", "-q", "-am", "ignore logs"])?;
+    let index = || Repository::discover(&project).expect("repository").read_index();
+
+    fs::write(project.join("debug.log"), "")?;
+    fs::create_dir_all(project.join("packages/app/dist"))?;
+    fs::write(project.join("packages/app/dist/index.js"), "")?;
+    fs::create_dir_all(project.join("vendor/lib/.git"))?;
+    fs::write(project.join("vendor/lib/README.md"), "")?;
+    assert!(!index()?.has_untracked(&project));
+    assert_eq!(scan(temp_dir.path(), true)?, vec![project.join("node_modules")]);
+
+    fs::create_dir_all(project.join("src"))?;
+    fs::write(project.join("src/new.js"), "")?;
+    assert!(index()?.has_untracked(&project));
+    assert!(scan(temp_dir.path(), true)?.is_empty());
+
+    Ok(())
+}
+
+/// Test that a version 4 index written by git is understood
+#[test]
+fn test_index_version_4_from_git() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let project = temp_dir.path().join("project");
+    if !create_repo(&project)? {
+        return Ok(());
+    }
+    git(&project, &["update-index", "--index-version", "4"])?;
+
+    let index = Repository::discover(&project).expect("repository").read_index()?;
+
+    assert!(index.tracks(Path::new("package.json")));
+    assert!(!index.has_changes(&project));
+
+    Ok(())
+}
+
+/// Test that linked worktrees use their own index
+#[test]
+fn test_linked_worktree() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let main = temp_dir.path().join("main");
+    if !create_repo(&main)? {
+        return Ok(());
+    }
+    let linked = temp_dir.path().join("linked");
+    git(&main, &["worktree", "add", "-q", linked.to_str().unwrap()])?;
+    common::create_test_directory_with_content(&linked.join("node_modules"), 1)?;
+
+    let repo = Repository::discover(&linked).expect("repository");
+    assert_eq!(repo.work_tree(), linked);
+    assert!(repo.git_dir().starts_with(main.join(".git/worktrees")));
+    assert!(!repo.read_index()?.has_changes(&linked));
+
+    fs::write(linked.join("package.json"), "{\"dirty\": true}")?;
+    let targets = scan(temp_dir.path(), true)?;
+    assert_eq!(targets, vec![main.join("node_modules")]);
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+//! Unit tests for ignore_file module
+
+use anyhow::Result;
+use nuke_node_modules::ignore_file::{IgnoreRules, IGNORE_FILE};
+use nuke_node_modules::scanner::Scanner;
+use std::fs;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+mod common;
+
+fn scan(temp_dir: &TempDir) -> Result<Vec<PathBuf>> {
+    Scanner::new(temp_dir.path(), &[]).find_node_modules_dirs()
+}
+
+/// Test that directories listed in a root .nukeignore are skipped
+#[test]
+fn test_root_ignore_file() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    common::create_scanner_test_structure(&temp_dir)?;
+    fs::write(temp_dir.path().join(IGNORE_FILE), "# keep these\nvendor/\ndeep\n")?;
+
+    let targets = scan(&temp_dir)?;
+
+    assert_eq!(targets.len(), 4);
+    assert!(!targets.iter().any(|p| p.starts_with(temp_dir.path().join("vendor"))));
+    assert!(!targets.iter().any(|p| p.starts_with(temp_dir.path().join("deep"))));
+
+    Ok(())
+}
+
+/// Test that a leading slash anchors a pattern to the directory of its file
+#[test]
+fn test_anchored_patterns() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let base = temp_dir.path();
+    fs::create_dir_all(base.join("app/node_modules"))?;
+    fs::create_dir_all(base.join("nested/app/node_modules"))?;
+    fs::write(base.join(IGNORE_FILE), "/app\n")?;
+
+    assert_eq!(scan(&temp_dir)?, vec![base.join("nested/app/node_modules")]);
+
+    Ok(())
+}
+
+/// Test that a deeper .nukeignore can re-include what a parent ignored
+#[test]
+fn test_nested_negation() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let base = temp_dir.path();
+    fs::create_dir_all(base.join("old-site/node_modules"))?;
+    fs::create_dir_all(base.join("work/old-but-active/node_modules"))?;
+    fs::create_dir_all(base.join("work/old-archive/node_modules"))?;
+    fs::write(base.join(IGNORE_FILE), "old-*\n")?;
+    fs::write(base.join("work").join(IGNORE_FILE), "!old-but-active\n")?;
+
+    assert_eq!(scan(&temp_dir)?, vec![base.join("work/old-but-active/node_modules")]);
+
+    Ok(())
+}
+
+/// Test that a node_modules directory itself can be ignored
+#[test]
+fn test_ignore_target_directly() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let base = temp_dir.path();
+    fs::create_dir_all(base.join("keep/node_modules"))?;
+    fs::create_dir_all(base.join("other/node_modules"))?;
+    fs::write(base.join("keep").join(IGNORE_FILE), "node_modules\n")?;
+
+    assert_eq!(scan(&temp_dir)?, vec![base.join("other/node_modules")]);
+
+    Ok(())
+}
+
+/// Test that a directory merely named like a target still has its ignore file read
+#[test]
+fn test_ignore_file_in_artifact_named_directory() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let base = temp_dir.path();
+    // No Cargo.toml, so `target` is an ordinary directory that gets walked
+    fs::create_dir_all(base.join("proj/target/sub/node_modules"))?;
+    fs::create_dir_all(base.join("proj/target/kept/node_modules"))?;
+    fs::write(base.join("proj/target").join(IGNORE_FILE), "sub\n")?;
+
+    let scanner = Scanner::new(base, &[]);
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(2).build()?;
+    let expected = vec![base.join("proj/target/kept/node_modules")];
+
+    assert_eq!(scanner.find_node_modules_dirs()?, expected);
+    assert_eq!(scanner.find_node_modules_dirs_parallel(&pool)?, expected);
+
+    Ok(())
+}
+
+/// Test that directories without an ignore file share their parent's rules
+#[test]
+fn test_rules_inherit_from_parent() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let base = temp_dir.path();
+    fs::create_dir_all(base.join("a/b"))?;
+    fs::write(base.join(IGNORE_FILE), "cache/\n")?;
+
+    let root = IgnoreRules::root(base);
+    let b = root.descend(&base.join("a")).descend(&base.join("a/b"));
+
+    assert!(b.is_ignored(&base.join("a/b/cache"), true));
+    assert!(!b.is_ignored(&base.join("a/b/cache"), false));
+    assert!(!b.is_ignored(&base.join("a/b/src"), true));
+
+    Ok(())
+}
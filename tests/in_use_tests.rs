@@ -0,0 +1,96 @@
+//! Unit tests for in_use module
+
+#![cfg(target_os = "linux")]
+
+use anyhow::Result;
+use nuke_node_modules::cleaner::Cleaner;
+use nuke_node_modules::in_use::ProcessSnapshot;
+use nuke_node_modules::report::TargetStatus;
+use nuke_node_modules::NukeError;
+use std::fs::{self, File};
+use std::process::{Child, Command, Stdio};
+use tempfile::TempDir;
+
+mod common;
+
+/// A child process that is killed when the test ends, even on failure
+struct Holder(Child);
+
+impl Drop for Holder {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// Spawn `sleep` with a file inside `node_modules` open as its stdin
+fn hold_file_open(file: &std::path::Path) -> Result<Holder> {
+    let child = Command::new("sleep")
+        .arg("30")
+        .stdin(Stdio::from(File::open(file)?))
+        .spawn()?;
+    Ok(Holder(child))
+}
+
+#[test]
+fn test_open_file_marks_target_in_use() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let busy = temp_dir.path().join("busy/node_modules");
+    let idle = temp_dir.path().join("idle/node_modules");
+    common::create_test_directory_with_content(&busy, 1)?;
+    common::create_test_directory_with_content(&idle, 1)?;
+
+    let holder = hold_file_open(&busy.join("file_0.txt"))?;
+    let snapshot = ProcessSnapshot::capture();
+
+    let user = snapshot.user_of(&busy).expect("busy target should be in use");
+    assert_eq!(user.pid, holder.0.id());
+    assert!(snapshot.user_of(&idle).is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_working_directory_marks_target_in_use() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let busy = temp_dir.path().join("busy/node_modules/.bin");
+    fs::create_dir_all(&busy)?;
+
+    let _holder = Holder(Command::new("sleep").arg("30").current_dir(&busy).spawn()?);
+
+    assert!(ProcessSnapshot::capture().user_of(&temp_dir.path().join("busy/node_modules")).is_some());
+
+    Ok(())
+}
+
+#[test]
+fn test_cleaner_skips_targets_in_use() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let busy = temp_dir.path().join("busy/node_modules");
+    let idle = temp_dir.path().join("idle/node_modules");
+    common::create_test_directory_with_content(&busy, 1)?;
+    common::create_test_directory_with_content(&idle, 1)?;
+
+    let _holder = hold_file_open(&busy.join("file_0.txt"))?;
+
+    let cleaner = Cleaner::new(Some(2), false).with_skip_in_use(true);
+    let stats = cleaner.delete_directories(vec![busy.clone(), idle.clone()])?;
+
+    assert!(busy.exists());
+    assert!(!idle.exists());
+    assert_eq!(stats.directories_deleted, 1);
+    assert_eq!(stats.directories_in_use, 1);
+    assert_eq!(stats.directories_failed, 0);
+
+    let outcome = stats.targets.iter().find(|outcome| outcome.path == busy).unwrap();
+    assert_eq!(outcome.status, TargetStatus::InUse);
+    match &outcome.error {
+        Some(NukeError::Busy { path, reason }) => {
+            assert_eq!(path, &busy);
+            assert!(reason.contains("sleep"));
+        }
+        other => panic!("expected a Busy error, got {:?}", other),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,137 @@
+//! Unit tests for journal module
+
+use anyhow::Result;
+use nuke_node_modules::cleaner::Cleaner;
+use nuke_node_modules::journal::{read_entries, summarize_runs, unfinished_targets, Journal};
+use nuke_node_modules::report::TargetStatus;
+use nuke_node_modules::{cleanup_node_modules, Config};
+use std::fs;
+use std::sync::Arc;
+use tempfile::TempDir;
+
+mod common;
+
+#[test]
+fn test_cleaner_writes_pending_and_outcome_entries() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let journal_path = temp_dir.path().join("state/journal.ndjson");
+    let root = temp_dir.path().join("projects");
+    let target = root.join("app/node_modules");
+    common::create_test_directory_with_content(&target, 2)?;
+
+    let journal = Arc::new(Journal::open(&journal_path, &root)?);
+    let cleaner = Cleaner::new(Some(1), false).with_journal(Some(Arc::clone(&journal)));
+    cleaner.delete_directories(vec![target.clone()])?;
+
+    let entries = read_entries(&journal_path)?;
+    assert_eq!(entries.len(), 2);
+    assert!(entries.iter().all(|e| e.run == journal.run() && e.target == target && e.root == root));
+    assert_eq!(entries[0].outcome, None);
+    assert_eq!(entries[1].outcome, Some(TargetStatus::Deleted));
+    assert!(entries[1].bytes.is_some_and(|bytes| bytes > 0));
+
+    Ok(())
+}
+
+#[test]
+fn test_summaries_and_unfinished_targets() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let journal_path = temp_dir.path().join("journal.ndjson");
+    let root = temp_dir.path().join("projects");
+    let done = root.join("done/node_modules");
+    let left = root.join("left/node_modules");
+    common::create_test_directory_with_content(&done, 1)?;
+    common::create_test_directory_with_content(&left, 1)?;
+
+    // A run that was killed after deleting one of its two targets
+    let journal = Journal::open(&journal_path, &root)?;
+    journal.record_pending(&[done.clone(), left.clone()]);
+    let cleaner = Cleaner::new(Some(1), false).with_journal(None);
+    let stats = cleaner.delete_directories(vec![done.clone()])?;
+    journal.record_outcome(&stats.targets[0]);
+
+    let entries = read_entries(&journal_path)?;
+    let summaries = summarize_runs(&entries);
+    assert_eq!(summaries.len(), 1);
+    assert_eq!(summaries[0].targets, 2);
+    assert_eq!(summaries[0].deleted, 1);
+    assert_eq!(summaries[0].unfinished, 1);
+
+    let (run, targets) = unfinished_targets(&entries, &root).expect("run should be unfinished");
+    assert_eq!(run, journal.run());
+    assert_eq!(targets, vec![left]);
+    assert!(unfinished_targets(&entries, temp_dir.path()).is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_staged_targets_are_journaled_as_staged() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let journal_path = temp_dir.path().join("journal.ndjson");
+    let root = temp_dir.path().join("projects");
+    let target = root.join("app/node_modules");
+    common::create_test_directory_with_content(&target, 1)?;
+
+    let journal = Arc::new(Journal::open(&journal_path, &root)?);
+    let cleaner = Cleaner::new(Some(1), false).with_fast(true).with_journal(Some(journal));
+    let stats = cleaner.delete_directories(vec![target])?;
+    nuke_node_modules::staging::delete_staged(&stats.pending_deletion, cleaner.thread_pool());
+
+    let entries = read_entries(&journal_path)?;
+    assert_eq!(entries[1].outcome, Some(TargetStatus::Staged));
+    let summaries = summarize_runs(&entries);
+    assert_eq!((summaries[0].deleted, summaries[0].staged, summaries[0].unfinished), (0, 1, 0));
+    assert!(unfinished_targets(&entries, &root).is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_truncated_lines_are_skipped() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let journal_path = temp_dir.path().join("journal.ndjson");
+
+    let journal = Journal::open(&journal_path, temp_dir.path())?;
+    journal.record_pending(&[temp_dir.path().join("a/node_modules")]);
+    let mut contents = fs::read_to_string(&journal_path)?;
+    contents.push_str("{\"run\":\"trunc");
+    fs::write(&journal_path, contents)?;
+
+    assert_eq!(read_entries(&journal_path)?.len(), 1);
+    assert!(read_entries(&temp_dir.path().join("missing.ndjson"))?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_resume_finishes_interrupted_run() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let journal_path = temp_dir.path().join("journal.ndjson");
+    let root = temp_dir.path().join("projects");
+    let gone = root.join("gone/node_modules");
+    let left = root.join("left/node_modules");
+    let unrelated = root.join("unrelated/node_modules");
+    common::create_test_directory_with_content(&left, 1)?;
+    common::create_test_directory_with_content(&unrelated, 1)?;
+
+    Journal::open(&journal_path, &root)?.record_pending(&[gone, left.clone()]);
+
+    let config = Config {
+        quiet: true,
+        no_confirm: true,
+        resume: true,
+        journal: Some(journal_path.clone()),
+        ..Default::default()
+    };
+    let stats = cleanup_node_modules(&root, &config)?;
+
+    assert_eq!(stats.directories_deleted, 1);
+    assert!(!left.exists());
+    assert!(unrelated.exists());
+
+    // The resumed run is journaled too, so there is nothing left to resume
+    assert!(unfinished_targets(&read_entries(&journal_path)?, &root).is_none());
+
+    Ok(())
+}
@@ -1,7 +1,11 @@
 //! Unit tests for lib module and main functions
 
 use anyhow::Result;
-use nuke_node_modules::{cleanup_node_modules, Config, format_bytes};
+use nuke_node_modules::cleaner::SortOrder;
+use nuke_node_modules::{
+    cleanup_node_modules, format_age, format_bytes, parse_age, parse_bytes, report_usage, scan_targets, Config,
+};
+use std::time::Duration;
 use std::fs;
 use tempfile::TempDir;
 
@@ -60,6 +64,32 @@ fn test_format_bytes() {
 }
 
 
+/// Test that an interrupt during the scan stops the run before anything is listed or deleted
+#[test]
+fn test_interrupt_before_deleting() -> Result<()> {
+    use nuke_node_modules::report::TargetStatus;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    let temp_dir = TempDir::new()?;
+    common::create_lib_test_structure(&temp_dir)?;
+
+    let config = Config {
+        dry_run: true,
+        quiet: true,
+        cancel_flag: Some(Arc::new(AtomicBool::new(true))),
+        ..Default::default()
+    };
+
+    let stats = cleanup_node_modules(temp_dir.path(), &config)?;
+    assert_eq!(stats.directories_found, 3);
+    assert_eq!(stats.directories_cancelled, 3);
+    assert!(stats.targets.iter().all(|t| t.status == TargetStatus::Cancelled));
+    assert!(temp_dir.path().join("project1/node_modules").exists());
+
+    Ok(())
+}
+
 /// Test user cancellation flow
 #[test]
 fn test_cleanup_user_cancellation() -> Result<()> {
@@ -121,6 +151,108 @@ fn test_format_bytes_all_units() {
     assert!(format_bytes(2199023255552).contains("TB")); // 2 TB
 }
 
+/// Test parsing of human-friendly ages
+#[test]
+fn test_parse_age() -> Result<()> {
+    assert_eq!(parse_age("30d")?, Duration::from_secs(30 * 24 * 60 * 60));
+    assert_eq!(parse_age("12h")?, Duration::from_secs(12 * 60 * 60));
+    assert_eq!(parse_age("2w")?, Duration::from_secs(14 * 24 * 60 * 60));
+    assert_eq!(parse_age("90m")?, Duration::from_secs(90 * 60));
+    assert_eq!(parse_age("7")?, Duration::from_secs(7 * 24 * 60 * 60)); // Bare number is days
+
+    assert!(parse_age("").is_err());
+    assert!(parse_age("d").is_err());
+    assert!(parse_age("10 fortnights").is_err());
+
+    Ok(())
+}
+
+/// Test parsing of human-friendly sizes
+#[test]
+fn test_parse_bytes() -> Result<()> {
+    assert_eq!(parse_bytes("512")?, 512);
+    assert_eq!(parse_bytes("1K")?, 1024);
+    assert_eq!(parse_bytes("100M")?, 100 * 1024 * 1024);
+    assert_eq!(parse_bytes("20G")?, 20 * 1024 * 1024 * 1024);
+    assert_eq!(parse_bytes("1.5 GB")?, 1610612736);
+    assert_eq!(parse_bytes("2tb")?, 2199023255552);
+
+    // Everything format_bytes prints can be parsed back
+    assert_eq!(parse_bytes(&format_bytes(1048576))?, 1048576);
+
+    assert!(parse_bytes("").is_err());
+    assert!(parse_bytes("lots").is_err());
+    assert!(parse_bytes("10 PB").is_err());
+
+    Ok(())
+}
+
+/// Test that min/max size limits filter targets
+#[test]
+fn test_size_limits() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+
+    common::create_test_directory_with_content(&temp_dir.path().join("tiny/node_modules"), 1)?;
+    let big = temp_dir.path().join("big/node_modules");
+    fs::create_dir_all(&big)?;
+    fs::write(big.join("bundle.js"), "x".repeat(64 * 1024))?;
+
+    let config = Config {
+        min_size: Some(16 * 1024),
+        quiet: true,
+        no_confirm: true,
+        ..Default::default()
+    };
+
+    let stats = cleanup_node_modules(temp_dir.path(), &config)?;
+    assert_eq!(stats.directories_deleted, 1);
+    assert!(!big.exists());
+    assert!(temp_dir.path().join("tiny/node_modules").exists());
+
+    Ok(())
+}
+
+/// Test that dry runs measure sizes and --top keeps the largest targets
+#[test]
+fn test_dry_run_sizes_and_top() -> Result<()> {
+    use nuke_node_modules::cleaner::SortOrder;
+
+    let temp_dir = TempDir::new()?;
+    for (project, files) in [("small", 1), ("medium", 5), ("large", 20)] {
+        common::create_test_directory_with_content(&temp_dir.path().join(project).join("node_modules"), files)?;
+    }
+
+    let config = Config {
+        dry_run: true,
+        quiet: true,
+        sort: SortOrder::Size,
+        top: Some(2),
+        ..Default::default()
+    };
+
+    let stats = cleanup_node_modules(temp_dir.path(), &config)?;
+
+    assert_eq!(stats.directories_found, 3);
+    assert_eq!(stats.directories_selected, 2);
+    assert_eq!(stats.targets.len(), 2);
+    assert!(stats.targets[0].path.ends_with("large/node_modules"));
+    assert!(stats.targets[1].path.ends_with("medium/node_modules"));
+
+    let listed: u64 = stats.targets.iter().filter_map(|t| t.bytes).sum();
+    assert!(stats.bytes_reclaimable > 0);
+    assert_eq!(stats.bytes_reclaimable, listed);
+
+    Ok(())
+}
+
+/// Test formatting of project ages
+#[test]
+fn test_format_age() {
+    assert_eq!(format_age(Duration::from_secs(5 * 60)), "5 minutes");
+    assert_eq!(format_age(Duration::from_secs(3 * 60 * 60)), "3 hours");
+    assert_eq!(format_age(Duration::from_secs(45 * 24 * 60 * 60)), "45 days");
+}
+
 /// Test cleanup with verbose output (non-quiet mode)
 #[test]
 fn test_cleanup_verbose_output() -> Result<()> {
@@ -181,4 +313,273 @@ fn test_cleanup_no_directories_found_verbose() -> Result<()> {
 }
 
 // Note: Testing the main function would require more complex integration testing
-// since it involves CLI parsing, file system operations, and process exit codes
\ No newline at end of file
+// since it involves CLI parsing, file system operations, and process exit codes
+/// Test that pipelined mode deletes every target found
+#[test]
+fn test_pipeline_mode() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    common::create_lib_test_structure(&temp_dir)?;
+
+    let config = Config {
+        pipeline: true,
+        no_confirm: true,
+        ignore_workspaces: true,
+        quiet: true,
+        ..Default::default()
+    };
+
+    let stats = cleanup_node_modules(temp_dir.path(), &config)?;
+
+    assert_eq!(stats.directories_found, 3);
+    assert_eq!(stats.directories_deleted, 3);
+    assert!(!temp_dir.path().join("project1/node_modules").exists());
+    assert!(!temp_dir.path().join("nested/project3/node_modules").exists());
+
+    Ok(())
+}
+
+/// Test that pipelined mode refuses options that need the full list of targets
+#[test]
+fn test_pipeline_rejects_incompatible_options() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    common::create_lib_test_structure(&temp_dir)?;
+
+    for config in [
+        Config { pipeline: true, ignore_workspaces: true, quiet: true, ..Default::default() },
+        Config { pipeline: true, no_confirm: true, quiet: true, ..Default::default() },
+        Config { pipeline: true, no_confirm: true, ignore_workspaces: true, quiet: true, top: Some(1), ..Default::default() },
+        Config { pipeline: true, no_confirm: true, ignore_workspaces: true, quiet: true, min_size: Some(1), ..Default::default() },
+    ] {
+        assert!(cleanup_node_modules(temp_dir.path(), &config).is_err());
+    }
+    assert!(temp_dir.path().join("project1/node_modules").exists());
+
+    Ok(())
+}
+
+/// Test that a symlinked node_modules is unlinked by default and deleted through on request
+#[cfg(unix)]
+#[test]
+fn test_symlinked_node_modules_deletion() -> Result<()> {
+    use std::os::unix::fs::symlink;
+
+    let temp_dir = TempDir::new()?;
+    let base = temp_dir.path();
+    let store = base.join("store/linked-pkg");
+    fs::create_dir_all(&store)?;
+    fs::write(store.join("index.js"), "module.exports = 1")?;
+    fs::create_dir_all(base.join("app"))?;
+    symlink(&store, base.join("app/node_modules"))?;
+
+    let config = Config {
+        no_confirm: true,
+        quiet: true,
+        ..Default::default()
+    };
+    let stats = cleanup_node_modules(base, &config)?;
+    assert_eq!(stats.directories_deleted, 1);
+    assert!(fs::symlink_metadata(base.join("app/node_modules")).is_err());
+    assert!(store.join("index.js").exists());
+
+    symlink(&store, base.join("app/node_modules"))?;
+    let config = Config {
+        delete_symlinked: true,
+        ..config
+    };
+    let stats = cleanup_node_modules(base, &config)?;
+    assert_eq!(stats.directories_deleted, 1);
+    assert!(stats.bytes_freed_apparent > 0);
+    assert!(fs::symlink_metadata(base.join("app/node_modules")).is_err());
+    assert!(!store.exists());
+
+    Ok(())
+}
+
+/// Test that deleting through a symlink that leaves the scan root is refused for that target only
+#[cfg(unix)]
+#[test]
+fn test_symlinked_node_modules_outside_root() -> Result<()> {
+    use nuke_node_modules::NukeError;
+    use std::os::unix::fs::symlink;
+
+    let temp_dir = TempDir::new()?;
+    let root = temp_dir.path().join("root");
+    let outside = temp_dir.path().join("outside");
+    fs::create_dir_all(root.join("app"))?;
+    fs::create_dir_all(root.join("other/node_modules"))?;
+    fs::create_dir_all(&outside)?;
+    symlink(&outside, root.join("app/node_modules"))?;
+
+    let config = Config {
+        no_confirm: true,
+        quiet: true,
+        delete_symlinked: true,
+        ..Default::default()
+    };
+
+    let stats = cleanup_node_modules(&root, &config)?;
+    assert_eq!(stats.directories_deleted, 1);
+    assert_eq!(stats.directories_failed, 1);
+    assert!(matches!(stats.errors().next(), Some(NukeError::SafetyViolation { .. })));
+    assert!(outside.exists());
+    assert!(fs::symlink_metadata(root.join("app/node_modules")).is_ok());
+    assert!(!root.join("other/node_modules").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_scan_targets_measures_without_deleting() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    common::create_test_directory_with_content(&temp_dir.path().join("small/node_modules"), 1)?;
+    common::create_test_directory_with_content(&temp_dir.path().join("large/node_modules"), 20)?;
+
+    let config = Config {
+        sort: SortOrder::Size,
+        top: Some(1),
+        ..Default::default()
+    };
+    let infos = scan_targets(temp_dir.path(), &config)?;
+
+    assert_eq!(infos.len(), 1);
+    assert_eq!(infos[0].path, temp_dir.path().join("large/node_modules"));
+    assert!(infos[0].bytes > 0);
+    assert!(temp_dir.path().join("small/node_modules").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_report_usage_groups_by_project() -> Result<()> {
+    use nuke_node_modules::artifact::ArtifactKind;
+
+    let temp_dir = TempDir::new()?;
+    let web = temp_dir.path().join("web");
+    fs::create_dir_all(&web)?;
+    fs::write(web.join("package.json"), "{}")?;
+    common::create_test_directory_with_content(&web.join("node_modules"), 10)?;
+    common::create_test_directory_with_content(&web.join(".next"), 10)?;
+    common::create_test_directory_with_content(&temp_dir.path().join("cli/node_modules"), 1)?;
+
+    let config = Config {
+        kinds: vec![ArtifactKind::NodeModules, ArtifactKind::Next],
+        ..Default::default()
+    };
+    let projects = report_usage(temp_dir.path(), &config)?;
+
+    assert_eq!(projects.len(), 2);
+    assert_eq!(projects[0].project, web);
+    assert_eq!(projects[0].targets, vec![web.join(".next"), web.join("node_modules")]);
+    assert!(projects[0].bytes > projects[1].bytes);
+    assert!(web.join("node_modules").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_workspaces_are_deleted_or_skipped_whole() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let mono = temp_dir.path().join("mono");
+    for project in ["mono", "mono/packages/a", "mono/packages/b", "solo"] {
+        common::create_test_directory_with_content(&temp_dir.path().join(project).join("node_modules"), 1)?;
+        fs::write(temp_dir.path().join(project).join("package.json"), "{}")?;
+    }
+    fs::write(mono.join("pnpm-workspace.yaml"), "packages:\n  - 'packages/*'\n")?;
+
+    // Excluding one member would leave the rest of the workspace broken
+    let config = Config {
+        exclude_patterns: vec!["**/packages/b/node_modules".to_string()],
+        no_confirm: true,
+        quiet: true,
+        ..Default::default()
+    };
+    let stats = cleanup_node_modules(temp_dir.path(), &config)?;
+    assert_eq!(stats.directories_deleted, 1);
+    assert!(!temp_dir.path().join("solo/node_modules").exists());
+    assert!(mono.join("node_modules").exists());
+    assert!(mono.join("packages/a/node_modules").exists());
+
+    // Scanning from inside the workspace can't reach the root's node_modules either
+    let stats = cleanup_node_modules(mono.join("packages"), &Config { exclude_patterns: Vec::new(), ..config.clone() })?;
+    assert_eq!(stats.directories_deleted, 0);
+    assert!(mono.join("packages/a/node_modules").exists());
+
+    let stats = cleanup_node_modules(mono.join("packages"), &Config { ignore_workspaces: true, ..config.clone() })?;
+    assert_eq!(stats.directories_deleted, 1);
+
+    let stats = cleanup_node_modules(&mono, &Config { exclude_patterns: Vec::new(), ..config })?;
+    assert_eq!(stats.directories_deleted, 2);
+    assert!(!mono.join("node_modules").exists());
+
+    Ok(())
+}
+
+/// Test that --top and reclaim goals pick or skip a workspace as a whole
+#[test]
+fn test_top_and_reclaim_treat_workspaces_as_units() -> Result<()> {
+    use nuke_node_modules::reclaim::ReclaimGoal;
+
+    let temp_dir = TempDir::new()?;
+    let mono = temp_dir.path().join("mono");
+    for (project, kib, days_old) in [("mono", 100, 300), ("mono/packages/a", 100, 300), ("mono/packages/b", 100, 300), ("solo", 150, 10)] {
+        let project = temp_dir.path().join(project);
+        common::write_file_aged(&project.join("node_modules/bundle.js"), &"x".repeat(kib * 1024), days_old)?;
+        common::write_file_aged(&project.join("package.json"), "{}", days_old)?;
+    }
+    common::write_file_aged(&mono.join("pnpm-workspace.yaml"), "packages:\n  - 'packages/*'\n", 300)?;
+
+    let base = Config {
+        dry_run: true,
+        quiet: true,
+        ..Default::default()
+    };
+    let paths = |stats: &nuke_node_modules::CleanupStats| -> Vec<std::path::PathBuf> {
+        stats.targets.iter().map(|t| t.path.clone()).collect()
+    };
+
+    // The workspace (300 KB in total) is the largest unit, so it is the top one
+    let stats = cleanup_node_modules(temp_dir.path(), &Config { sort: SortOrder::Size, top: Some(1), ..base.clone() })?;
+    assert_eq!(
+        paths(&stats),
+        vec![mono.join("node_modules"), mono.join("packages/a/node_modules"), mono.join("packages/b/node_modules")]
+    );
+
+    // The oldest unit is the whole workspace, even though one member would meet the goal
+    let stats = cleanup_node_modules(temp_dir.path(), &Config { reclaim: Some(ReclaimGoal::Bytes(50 * 1024)), ..base })?;
+    assert_eq!(stats.targets.len(), 3);
+    assert!(stats.bytes_reclaimable >= 300 * 1024);
+    assert!(temp_dir.path().join("mono/node_modules").exists());
+
+    Ok(())
+}
+
+/// Test that targets outside the size limits are counted by reason
+#[test]
+fn test_size_limits_report_filtered_targets() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    common::create_test_directory_with_content(&temp_dir.path().join("tiny/node_modules"), 1)?;
+    for (project, kib) in [("medium", 64), ("huge", 1024)] {
+        let node_modules = temp_dir.path().join(project).join("node_modules");
+        fs::create_dir_all(&node_modules)?;
+        fs::write(node_modules.join("bundle.js"), "x".repeat(kib * 1024))?;
+    }
+
+    let config = Config {
+        min_size: Some(parse_bytes("16K")?),
+        max_size: Some(parse_bytes("512 KB")?),
+        dry_run: true,
+        quiet: true,
+        ..Default::default()
+    };
+    let stats = cleanup_node_modules(temp_dir.path(), &config)?;
+
+    assert_eq!(stats.directories_found, 1);
+    assert!(stats.targets[0].path.ends_with("medium/node_modules"));
+    assert_eq!(stats.directories_too_small, 1);
+    assert_eq!(stats.directories_too_large, 1);
+
+    let inverted = Config { min_size: Some(1 << 30), ..config };
+    assert!(cleanup_node_modules(temp_dir.path(), &inverted).is_err());
+
+    Ok(())
+}
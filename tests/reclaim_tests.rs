@@ -0,0 +1,155 @@
+//! Unit tests for the reclaim module
+
+use anyhow::Result;
+use nuke_node_modules::cleaner::TargetInfo;
+use nuke_node_modules::reclaim::{available_space, select_for_reclaim, ReclaimGoal};
+use nuke_node_modules::{cleanup_node_modules, Config};
+use std::path::PathBuf;
+use std::time::Duration;
+use tempfile::TempDir;
+
+mod common;
+
+fn info(path: &str, bytes: u64, age_days: Option<u64>) -> TargetInfo {
+    TargetInfo {
+        path: PathBuf::from(path),
+        bytes,
+        apparent_bytes: bytes,
+        age: age_days.map(|d| Duration::from_secs(d * 86400)),
+    }
+}
+
+fn paths(targets: &[TargetInfo]) -> Vec<String> {
+    targets.iter().map(|t| t.path.display().to_string()).collect()
+}
+
+/// Test that the oldest projects are picked first and selection stops once the goal is reached
+#[test]
+fn test_select_oldest_first_until_goal() {
+    let targets = vec![
+        info("/new/node_modules", 500, Some(1)),
+        info("/old/node_modules", 100, Some(400)),
+        info("/unknown/node_modules", 1000, None),
+        info("/mid/node_modules", 200, Some(90)),
+    ];
+
+    let selected = select_for_reclaim(targets.clone(), 250);
+    assert_eq!(paths(&selected), ["/old/node_modules", "/mid/node_modules"]);
+
+    let selected = select_for_reclaim(targets, 10_000);
+    assert_eq!(
+        paths(&selected),
+        ["/old/node_modules", "/mid/node_modules", "/new/node_modules", "/unknown/node_modules"]
+    );
+}
+
+/// Test that larger targets win when projects are the same age
+#[test]
+fn test_select_prefers_larger_on_equal_age() {
+    let targets = vec![
+        info("/a/node_modules", 10, Some(30)),
+        info("/b/node_modules", 50, Some(30)),
+    ];
+
+    let selected = select_for_reclaim(targets, 40);
+    assert_eq!(paths(&selected), ["/b/node_modules"]);
+}
+
+/// Test that a goal of zero bytes selects nothing
+#[test]
+fn test_select_nothing_needed() {
+    let selected = select_for_reclaim(vec![info("/a/node_modules", 10, Some(1))], 0);
+    assert!(selected.is_empty());
+}
+
+/// Test querying the free space of a filesystem
+#[cfg(unix)]
+#[test]
+fn test_available_space() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    assert!(available_space(temp_dir.path())? > 0);
+    Ok(())
+}
+
+/// Test how many bytes each kind of goal still needs
+#[cfg(unix)]
+#[test]
+fn test_bytes_needed() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+
+    assert_eq!(ReclaimGoal::Bytes(1234).bytes_needed(temp_dir.path())?, 1234);
+    assert_eq!(ReclaimGoal::UntilFree(1).bytes_needed(temp_dir.path())?, 0);
+    assert!(ReclaimGoal::UntilFree(u64::MAX).bytes_needed(temp_dir.path())? > 0);
+
+    Ok(())
+}
+
+/// Test that a reclaim run only deletes the oldest project when that is enough
+#[test]
+fn test_cleanup_with_reclaim_goal() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let old = temp_dir.path().join("old");
+    let new = temp_dir.path().join("new");
+
+    common::write_file_aged(&old.join("package.json"), "{}", 200)?;
+    common::write_file_aged(&old.join("node_modules/pkg/index.js"), "x", 200)?;
+    common::write_file_aged(&new.join("package.json"), "{}", 1)?;
+    common::write_file_aged(&new.join("node_modules/pkg/index.js"), "x", 1)?;
+
+    let config = Config {
+        no_confirm: true,
+        quiet: true,
+        reclaim: Some(ReclaimGoal::Bytes(1)),
+        ..Default::default()
+    };
+
+    let stats = cleanup_node_modules(temp_dir.path(), &config)?;
+
+    assert_eq!(stats.directories_deleted, 1);
+    assert!(!old.join("node_modules").exists());
+    assert!(new.join("node_modules").exists());
+
+    Ok(())
+}
+
+/// Test that nothing is deleted when the free-space goal is already met
+#[cfg(unix)]
+#[test]
+fn test_cleanup_until_free_already_met() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    common::create_lib_test_structure(&temp_dir)?;
+
+    let config = Config {
+        no_confirm: true,
+        quiet: true,
+        reclaim: Some(ReclaimGoal::UntilFree(1)),
+        ..Default::default()
+    };
+
+    let stats = cleanup_node_modules(temp_dir.path(), &config)?;
+
+    assert_eq!(stats.directories_deleted, 0);
+    assert!(temp_dir.path().join("project1/node_modules").exists());
+
+    Ok(())
+}
+
+/// Test that a reclaim goal is refused in trash mode, which frees no space
+#[test]
+fn test_reclaim_refuses_trash() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    common::create_lib_test_structure(&temp_dir)?;
+
+    let config = Config {
+        no_confirm: true,
+        quiet: true,
+        trash: true,
+        reclaim: Some(ReclaimGoal::Bytes(1)),
+        ..Default::default()
+    };
+
+    assert!(cleanup_node_modules(temp_dir.path(), &config).is_err());
+    assert!(temp_dir.path().join("project1/node_modules").exists());
+
+    Ok(())
+}
@@ -0,0 +1,131 @@
+//! Unit tests for reinstall module
+#![cfg(unix)]
+
+use anyhow::Result;
+use nuke_node_modules::reinstall::{projects_of, PackageManager, ReinstallStatus, Reinstaller};
+use std::ffi::OsString;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// Write an executable shell script named `name` into `bin`
+fn write_stub(bin: &Path, name: &str, script: &str) -> Result<()> {
+    fs::create_dir_all(bin)?;
+    let path = bin.join(name);
+    fs::write(&path, format!("#!/bin/sh\n{}\n", script))?;
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+    Ok(())
+}
+
+/// A `PATH` holding only the stub directory and the system shell utilities
+fn stub_path(bin: &Path) -> OsString {
+    let mut path = bin.as_os_str().to_owned();
+    path.push(":/usr/bin:/bin");
+    path
+}
+
+#[test]
+fn test_detect_from_lockfile() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let project = temp_dir.path();
+    assert_eq!(PackageManager::detect(project), None);
+
+    fs::write(project.join("package-lock.json"), "{}")?;
+    assert_eq!(PackageManager::detect(project), Some(PackageManager::Npm));
+
+    fs::write(project.join("bun.lockb"), "")?;
+    assert_eq!(PackageManager::detect(project), Some(PackageManager::Bun));
+
+    fs::write(project.join("yarn.lock"), "")?;
+    assert_eq!(PackageManager::detect(project), Some(PackageManager::Yarn));
+
+    fs::write(project.join("pnpm-lock.yaml"), "")?;
+    assert_eq!(PackageManager::detect(project), Some(PackageManager::Pnpm));
+
+    Ok(())
+}
+
+#[test]
+fn test_clean_install_commands() {
+    assert_eq!(PackageManager::Npm.command_line(), "npm ci");
+    assert_eq!(PackageManager::Yarn.command_line(), "yarn install --frozen-lockfile");
+    assert_eq!(PackageManager::Pnpm.command_line(), "pnpm install --frozen-lockfile");
+    assert_eq!(PackageManager::Bun.command_line(), "bun install --frozen-lockfile");
+}
+
+#[test]
+fn test_projects_of_only_node_modules_once_each() {
+    let targets = vec![
+        PathBuf::from("/w/b/node_modules"),
+        PathBuf::from("/w/a/node_modules"),
+        PathBuf::from("/w/a/.next"),
+        PathBuf::from("/w/c/target"),
+    ];
+
+    assert_eq!(projects_of(&targets), vec![PathBuf::from("/w/a"), PathBuf::from("/w/b")]);
+}
+
+#[test]
+fn test_reinstall_runs_clean_install_in_project() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let bin = temp_dir.path().join("bin");
+    write_stub(&bin, "npm", "echo \"$@\" > invoked; mkdir node_modules")?;
+    write_stub(&bin, "pnpm", "echo \"$@\" > invoked; mkdir node_modules")?;
+
+    let npm_project = temp_dir.path().join("npm-app");
+    let pnpm_project = temp_dir.path().join("pnpm-app");
+    let bare_project = temp_dir.path().join("bare");
+    for project in [&npm_project, &pnpm_project, &bare_project] {
+        fs::create_dir_all(project)?;
+    }
+    fs::write(npm_project.join("package-lock.json"), "{}")?;
+    fs::write(pnpm_project.join("pnpm-lock.yaml"), "")?;
+
+    let outcomes = Reinstaller::new(Some(2), false)
+        .with_search_path(Some(stub_path(&bin)))
+        .reinstall(&[npm_project.clone(), pnpm_project.clone(), bare_project.clone()]);
+
+    assert_eq!(outcomes.len(), 3);
+    assert_eq!(outcomes[0].manager, Some(PackageManager::Npm));
+    assert_eq!(outcomes[0].status, ReinstallStatus::Installed);
+    assert_eq!(outcomes[1].manager, Some(PackageManager::Pnpm));
+    assert_eq!(outcomes[1].status, ReinstallStatus::Installed);
+    assert_eq!(outcomes[2].status, ReinstallStatus::NoLockfile);
+
+    assert_eq!(fs::read_to_string(npm_project.join("invoked"))?.trim(), "ci");
+    assert_eq!(fs::read_to_string(pnpm_project.join("invoked"))?.trim(), "install --frozen-lockfile");
+    assert!(npm_project.join("node_modules").is_dir());
+    assert!(!bare_project.join("node_modules").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_reinstall_reports_failures() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let bin = temp_dir.path().join("bin");
+    write_stub(&bin, "yarn", "echo 'warning: resolving' >&2; echo 'error: lockfile needs update' >&2; exit 1")?;
+
+    let yarn_project = temp_dir.path().join("yarn-app");
+    let bun_project = temp_dir.path().join("bun-app");
+    fs::create_dir_all(&yarn_project)?;
+    fs::create_dir_all(&bun_project)?;
+    fs::write(yarn_project.join("yarn.lock"), "")?;
+    fs::write(bun_project.join("bun.lockb"), "")?;
+
+    let outcomes = Reinstaller::new(None, false)
+        .with_search_path(Some(stub_path(&bin)))
+        .reinstall(&[yarn_project, bun_project]);
+
+    assert_eq!(outcomes[0].status, ReinstallStatus::Failed);
+    let error = outcomes[0].error.as_deref().unwrap_or_default();
+    assert!(error.contains("yarn install --frozen-lockfile"));
+    assert!(error.ends_with("error: lockfile needs update"));
+
+    // No bun executable on the search path
+    assert_eq!(outcomes[1].status, ReinstallStatus::Failed);
+    assert!(outcomes[1].error.as_deref().unwrap_or_default().contains("could not run `bun`"));
+
+    Ok(())
+}
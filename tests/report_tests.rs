@@ -0,0 +1,153 @@
+//! Unit tests for report module
+
+use anyhow::Result;
+use nuke_node_modules::cleaner::Cleaner;
+use nuke_node_modules::report::{OutputFormat, TargetOutcome, TargetStatus};
+use nuke_node_modules::{cleanup_node_modules, CleanupStats, Config};
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+mod common;
+
+#[test]
+fn test_target_outcome_serialization() -> Result<()> {
+    let outcome = TargetOutcome {
+        path: PathBuf::from("/projects/app/node_modules"),
+        bytes: Some(2048),
+        apparent_bytes: Some(1500),
+        age_secs: Some(86400),
+        status: TargetStatus::WouldDelete,
+        error: None,
+    };
+
+    let value = serde_json::to_value(&outcome)?;
+    assert_eq!(value["path"], "/projects/app/node_modules");
+    assert_eq!(value["bytes"], 2048);
+    assert_eq!(value["apparent_bytes"], 1500);
+    assert_eq!(value["age_secs"], 86400);
+    assert_eq!(value["status"], "would_delete");
+    assert!(value["error"].is_null());
+
+    Ok(())
+}
+
+#[test]
+fn test_interrupted_statuses_serialization() -> Result<()> {
+    let value = serde_json::to_value(TargetStatus::PartiallyDeleted)?;
+    assert_eq!(value, "partially_deleted");
+
+    let value = serde_json::to_value(TargetStatus::Cancelled)?;
+    assert_eq!(value, "cancelled");
+
+    let stats = CleanupStats {
+        directories_failed: 1,
+        directories_partial: 1,
+        directories_cancelled: 3,
+        ..Default::default()
+    };
+    let value = serde_json::to_value(&stats)?;
+    assert_eq!(value["directories_partial"], 1);
+    assert_eq!(value["directories_cancelled"], 3);
+
+    Ok(())
+}
+
+#[test]
+fn test_cleanup_stats_serialization_skips_targets() -> Result<()> {
+    let stats = CleanupStats {
+        directories_found: 2,
+        directories_deleted: 1,
+        directories_failed: 1,
+        bytes_freed: 100,
+        ..Default::default()
+    };
+
+    let value = serde_json::to_value(&stats)?;
+    assert_eq!(value["directories_found"], 2);
+    assert_eq!(value["directories_failed"], 1);
+    assert_eq!(value["bytes_freed"], 100);
+    assert!(value.get("targets").is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_machine_readable_requires_no_confirm() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    common::create_lib_test_structure(&temp_dir)?;
+
+    let config = Config {
+        format: OutputFormat::Json,
+        ..Default::default()
+    };
+
+    assert!(cleanup_node_modules(temp_dir.path(), &config).is_err());
+    assert!(temp_dir.path().join("project1/node_modules").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_outcomes_are_recorded_per_target() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    common::create_lib_test_structure(&temp_dir)?;
+
+    let config = Config {
+        format: OutputFormat::Ndjson,
+        no_confirm: true,
+        ..Default::default()
+    };
+
+    let stats = cleanup_node_modules(temp_dir.path(), &config)?;
+
+    assert_eq!(stats.targets.len(), 3);
+    assert!(stats.targets.iter().all(|t| t.status == TargetStatus::Deleted));
+    assert!(stats.targets.iter().all(|t| t.bytes.is_some()));
+    assert!(stats.targets.windows(2).all(|w| w[0].path <= w[1].path));
+
+    Ok(())
+}
+
+#[test]
+fn test_dry_run_outcomes() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    common::create_lib_test_structure(&temp_dir)?;
+
+    let config = Config {
+        format: OutputFormat::Json,
+        dry_run: true,
+        ..Default::default()
+    };
+
+    let stats = cleanup_node_modules(temp_dir.path(), &config)?;
+
+    assert_eq!(stats.targets.len(), 3);
+    assert!(stats.targets.iter().all(|t| t.status == TargetStatus::WouldDelete));
+    // project1 has a package.json, so its age is known
+    let project1 = stats
+        .targets
+        .iter()
+        .find(|t| t.path.ends_with("project1/node_modules"))
+        .expect("project1 should be reported");
+    assert!(project1.age_secs.is_some());
+
+    Ok(())
+}
+
+#[test]
+fn test_age_is_only_looked_up_for_machine_readable_output() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    common::create_lib_test_structure(&temp_dir)?;
+    let target = || vec![temp_dir.path().join("project1/node_modules")];
+
+    let stats = Cleaner::new(Some(1), false).delete_directories(target())?;
+    assert_eq!(stats.targets[0].age_secs, None);
+
+    common::create_lib_test_structure(&temp_dir)?;
+    let stats = Cleaner::new(Some(1), false)
+        .with_output(OutputFormat::Json)
+        .delete_directories(target())?;
+    assert!(stats.targets[0].age_secs.is_some());
+
+    Ok(())
+}
@@ -0,0 +1,94 @@
+//! Unit tests for safety module
+
+use anyhow::Result;
+use nuke_node_modules::safety::{dangerous_root_reason, SafetyPolicy};
+use nuke_node_modules::{cleanup_node_modules, Config};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+#[cfg(unix)]
+#[test]
+fn test_system_roots_are_dangerous() {
+    assert!(dangerous_root_reason(Path::new("/"), None).is_some());
+    assert!(dangerous_root_reason(Path::new("/usr"), None).is_some());
+    assert!(dangerous_root_reason(Path::new("/usr/lib/node_modules"), None).is_some());
+    assert!(dangerous_root_reason(Path::new("/opt"), None).is_some());
+    assert!(dangerous_root_reason(Path::new("/usr/local/.."), None).is_some());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_projects_below_shared_roots_are_allowed() {
+    assert!(dangerous_root_reason(Path::new("/opt/projects"), None).is_none());
+    assert!(dangerous_root_reason(Path::new("/home/someone/code"), None).is_none());
+}
+
+#[test]
+fn test_home_is_dangerous_but_its_subdirectories_are_not() -> Result<()> {
+    let home = TempDir::new()?;
+    let projects = home.path().join("projects");
+    fs::create_dir(&projects)?;
+
+    assert!(dangerous_root_reason(home.path(), Some(home.path())).is_some());
+    assert!(dangerous_root_reason(&projects.join(".."), Some(home.path())).is_some());
+    assert!(dangerous_root_reason(&projects, Some(home.path())).is_none());
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_check_root_can_be_overridden() {
+    let policy = SafetyPolicy::default();
+    assert!(policy.check_root(Path::new("/")).is_err());
+
+    let policy = SafetyPolicy {
+        allow_dangerous_roots: true,
+        ..Default::default()
+    };
+    assert!(policy.check_root(Path::new("/")).is_ok());
+}
+
+#[test]
+fn test_second_confirmation_thresholds() {
+    let policy = SafetyPolicy {
+        confirm_above_count: Some(10),
+        confirm_above_bytes: Some(1024),
+        ..Default::default()
+    };
+
+    assert!(policy.second_confirmation_reason(10, 1024).is_none());
+    assert!(policy.second_confirmation_reason(11, 0).is_some());
+    assert!(policy.second_confirmation_reason(1, 1025).is_some());
+
+    let policy = SafetyPolicy {
+        confirm_above_count: None,
+        confirm_above_bytes: None,
+        ..Default::default()
+    };
+    assert!(policy.second_confirmation_reason(usize::MAX, u64::MAX).is_none());
+}
+
+#[test]
+fn test_no_confirm_skips_second_confirmation() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    for i in 0..3 {
+        fs::create_dir_all(temp_dir.path().join(format!("project{}/node_modules", i)))?;
+    }
+
+    let config = Config {
+        quiet: true,
+        no_confirm: true,
+        safety: SafetyPolicy {
+            confirm_above_count: Some(1),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let stats = cleanup_node_modules(temp_dir.path(), &config)?;
+    assert_eq!(stats.directories_deleted, 3);
+
+    Ok(())
+}
@@ -206,6 +206,114 @@ fn test_should_exclude_patterns() -> Result<()> {
     Ok(())
 }
 
+/// Test which directories are pruned from the walk by exclusion patterns
+#[test]
+fn test_should_prune_patterns() {
+    use std::path::Path;
+
+    let exclude_patterns = vec![
+        "**/vendor/**".to_string(),
+        "**/archive-*".to_string(),
+        "**/node_modules".to_string(),
+    ];
+    let scanner = Scanner::new(".", &exclude_patterns);
+
+    // Everything below these directories is excluded
+    assert!(scanner.should_prune(Path::new("/project/vendor")));
+    assert!(scanner.should_prune(Path::new("/project/vendor/lib")));
+    assert!(scanner.should_prune(Path::new("/home/archive-2020")));
+
+    // Only exact matches are excluded, so the walk must continue
+    assert!(!scanner.should_prune(Path::new("/project/node_modules")));
+    assert!(!scanner.should_prune(Path::new("/project/vendors")));
+    assert!(!scanner.should_prune(Path::new("/project/src")));
+}
+
+/// Test that pruning excluded subtrees finds the same targets as filtering them afterwards
+#[test]
+fn test_pruned_scan_matches_filtered_scan() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    common::create_scanner_test_structure(&temp_dir)?;
+    fs::create_dir_all(temp_dir.path().join("vendor/deep/project/node_modules"))?;
+
+    let exclude_patterns = vec!["**/vendor/**".to_string(), "**/exclude-me*".to_string()];
+    let scanner = Scanner::new(temp_dir.path(), &exclude_patterns);
+
+    let pruned = scanner.find_node_modules_dirs()?;
+    let filtered: Vec<PathBuf> = Scanner::new(temp_dir.path(), &[])
+        .find_node_modules_dirs()?
+        .into_iter()
+        .filter(|path| !scanner.should_exclude(path))
+        .collect();
+
+    assert_eq!(pruned, filtered);
+    assert_eq!(pruned.len(), 4);
+
+    Ok(())
+}
+
+/// Test that the parallel walker finds exactly what the sequential one does
+#[test]
+fn test_parallel_scan_matches_sequential() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    common::create_scanner_test_structure(&temp_dir)?;
+    common::create_nested_node_modules_structure(&temp_dir)?;
+    fs::write(temp_dir.path().join(".nukeignore"), "deep/\n")?;
+
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(4).build()?;
+
+    for patterns in [vec![], vec!["**/vendor/**".to_string(), "**/exclude-me/node_modules".to_string()]] {
+        let scanner = Scanner::new(temp_dir.path(), &patterns);
+        let sequential = scanner.find_node_modules_dirs()?;
+        let parallel = scanner.find_node_modules_dirs_parallel(&pool)?;
+
+        assert!(!parallel.is_empty());
+        assert_eq!(parallel, sequential);
+    }
+
+    Ok(())
+}
+
+/// Test the parallel walker when the root itself is a target or is empty
+#[test]
+fn test_parallel_scan_root_edge_cases() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let root_target = temp_dir.path().join("node_modules");
+    fs::create_dir_all(root_target.join("pkg/node_modules"))?;
+    let empty = temp_dir.path().join("empty");
+    fs::create_dir_all(&empty)?;
+
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(2).build()?;
+
+    assert_eq!(Scanner::new(&root_target, &[]).find_node_modules_dirs_parallel(&pool)?, vec![root_target]);
+    assert!(Scanner::new(&empty, &[]).find_node_modules_dirs_parallel(&pool)?.is_empty());
+    assert!(Scanner::new(temp_dir.path().join("missing"), &[]).find_node_modules_dirs_parallel(&pool).is_err());
+
+    Ok(())
+}
+
+/// Test that the streaming scanner yields every target the batch scan finds
+#[test]
+fn test_scan_stream() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    common::create_scanner_test_structure(&temp_dir)?;
+
+    let expected = Scanner::new(temp_dir.path(), &[]).find_node_modules_dirs()?;
+    let mut streamed = Scanner::new(temp_dir.path(), &[])
+        .scan_stream()
+        .collect::<Result<Vec<_>>>()?;
+    streamed.sort();
+
+    assert_eq!(streamed, expected);
+
+    // Walk errors arrive as the last item
+    let mut missing = Scanner::new(temp_dir.path().join("missing"), &[]).scan_stream();
+    assert!(missing.next().is_some_and(|item| item.is_err()));
+    assert!(missing.next().is_none());
+
+    Ok(())
+}
+
 /// Test should_exclude with no exclusion patterns
 #[test]
 fn test_should_exclude_no_patterns() {
@@ -274,4 +382,138 @@ fn test_should_exclude_special_characters() {
 
     // Should not match non-scoped packages
     assert!(!scanner.should_exclude(Path::new("/project/scope/package")));  // No @ prefix
-}
\ No newline at end of file
+}
+
+/// Test that project age comes from project files, not node_modules
+#[test]
+fn test_project_age_ignores_node_modules() -> Result<()> {
+    use nuke_node_modules::scanner::project_age;
+
+    let temp_dir = TempDir::new()?;
+    let project = temp_dir.path().join("project");
+
+    common::write_file_aged(&project.join("package.json"), "{}", 40)?;
+    common::write_file_aged(&project.join("src/index.js"), "", 45)?;
+    // Freshly installed dependencies must not make the project look recent
+    fs::create_dir_all(project.join("node_modules/left-pad"))?;
+    fs::write(project.join("node_modules/left-pad/index.js"), "")?;
+
+    let age = project_age(&project.join("node_modules")).expect("age should be known");
+    let days = age.as_secs() / (24 * 60 * 60);
+    assert_eq!(days, 40);
+
+    Ok(())
+}
+
+/// Test that project age only looks at sources a few levels deep
+#[test]
+fn test_project_age_has_a_depth_cap() -> Result<()> {
+    use nuke_node_modules::scanner::project_age;
+
+    let temp_dir = TempDir::new()?;
+    let project = temp_dir.path().join("project");
+
+    common::write_file_aged(&project.join("package.json"), "{}", 40)?;
+    common::write_file_aged(&project.join("src/components/button.js"), "", 10)?;
+    common::write_file_aged(&project.join("src/a/b/c/d/deep.js"), "", 1)?;
+
+    let age = project_age(&project.join("node_modules")).expect("age should be known");
+    let days = age.as_secs() / (24 * 60 * 60);
+    assert_eq!(days, 10);
+
+    Ok(())
+}
+
+/// Test that the minimum age filter skips recently used projects
+#[test]
+fn test_min_age_filter() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+
+    common::write_file_aged(&temp_dir.path().join("stale/package.json"), "{}", 60)?;
+    fs::create_dir_all(temp_dir.path().join("stale/node_modules"))?;
+
+    common::write_file_aged(&temp_dir.path().join("active/package.json"), "{}", 60)?;
+    common::write_file_aged(&temp_dir.path().join("active/src/main.ts"), "", 2)?;
+    fs::create_dir_all(temp_dir.path().join("active/node_modules"))?;
+
+    let scanner = Scanner::new(temp_dir.path(), &[])
+        .with_min_age(Some(std::time::Duration::from_secs(30 * 24 * 60 * 60)));
+    let targets = scanner.find_node_modules_dirs()?;
+
+    assert_eq!(targets, vec![temp_dir.path().join("stale/node_modules")]);
+
+    Ok(())
+}
+
+/// Test that a symlinked node_modules is reported as a target without being followed
+#[cfg(unix)]
+#[test]
+fn test_symlinked_node_modules_is_target() -> Result<()> {
+    use std::os::unix::fs::symlink;
+
+    let temp_dir = TempDir::new()?;
+    let base = temp_dir.path();
+    fs::create_dir_all(base.join("store/pkg/node_modules"))?;
+    fs::create_dir_all(base.join("app"))?;
+    symlink(base.join("store/pkg"), base.join("app/node_modules"))?;
+
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(2).build()?;
+    let scanner = Scanner::new(base, &[]);
+    let expected = vec![base.join("app/node_modules"), base.join("store/pkg/node_modules")];
+
+    assert_eq!(scanner.find_node_modules_dirs()?, expected);
+    assert_eq!(scanner.find_node_modules_dirs_parallel(&pool)?, expected);
+
+    Ok(())
+}
+
+/// Test following symlinked directories, including loops and links leaving the root
+#[cfg(unix)]
+#[test]
+fn test_follow_symlinks() -> Result<()> {
+    use std::os::unix::fs::symlink;
+
+    let temp_dir = TempDir::new()?;
+    let root = temp_dir.path().join("root");
+    let outside = temp_dir.path().join("outside");
+    fs::create_dir_all(root.join("real/project/node_modules"))?;
+    fs::create_dir_all(outside.join("project/node_modules"))?;
+    symlink(&outside, root.join("elsewhere"))?;
+    symlink(root.join("real"), root.join("alias"))?;
+    symlink(&root, root.join("real/loop"))?;
+
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(2).build()?;
+
+    let scanner = Scanner::new(&root, &[]);
+    assert_eq!(scanner.find_node_modules_dirs()?, vec![root.join("real/project/node_modules")]);
+
+    // Followed links find the aliased project once and skip the one outside the root
+    let scanner = Scanner::new(&root, &[]).with_follow_symlinks(true);
+    let expected = vec![root.join("alias/project/node_modules")];
+    assert_eq!(scanner.find_node_modules_dirs()?, expected);
+    assert_eq!(scanner.find_node_modules_dirs_parallel(&pool)?, expected);
+
+    Ok(())
+}
+
+/// Test that targets resolving outside the scan root are refused
+#[cfg(unix)]
+#[test]
+fn test_validate_within_root() -> Result<()> {
+    use nuke_node_modules::scanner::validate_within_root;
+    use std::os::unix::fs::symlink;
+
+    let temp_dir = TempDir::new()?;
+    let root = temp_dir.path().join("root");
+    let outside = temp_dir.path().join("outside/node_modules");
+    fs::create_dir_all(root.join("app"))?;
+    fs::create_dir_all(&outside)?;
+    symlink(&outside, root.join("app/node_modules"))?;
+
+    let link = vec![root.join("app/node_modules")];
+    validate_within_root(&link, &root, false)?;
+    assert!(validate_within_root(&link, &root, true).is_err());
+    assert!(validate_within_root(&[outside], &root, false).is_err());
+
+    Ok(())
+}
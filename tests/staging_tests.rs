@@ -0,0 +1,151 @@
+//! Unit tests for staging module
+
+use anyhow::Result;
+use nuke_node_modules::cleaner::Cleaner;
+use nuke_node_modules::report::TargetStatus;
+use nuke_node_modules::scanner::Scanner;
+use nuke_node_modules::staging::{delete_staged, is_abandoned, is_staged, lock_path, stage};
+use nuke_node_modules::{cleanup_node_modules, Config};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+mod common;
+
+/// Pid far above any real pid_max, so no process can have it
+const DEAD_PID: u32 = 999_999_999;
+
+#[test]
+fn test_stage_renames_to_hidden_sibling() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let target = temp_dir.path().join("project/node_modules");
+    common::create_test_directory_with_content(&target, 2)?;
+
+    let staged = stage(&target)?;
+    assert!(!target.exists());
+    assert_eq!(staged.parent(), target.parent());
+    assert_eq!(
+        staged.file_name().unwrap().to_string_lossy(),
+        format!(".node_modules.nuking-{}", std::process::id())
+    );
+    assert!(staged.join("file_0.txt").exists());
+
+    // A second target with the same name gets a distinct staging path
+    fs::create_dir(&target)?;
+    let second = stage(&target)?;
+    assert_ne!(second, staged);
+    assert!(is_staged(&second));
+
+    Ok(())
+}
+
+#[test]
+fn test_is_staged_and_abandoned() {
+    assert!(is_staged(Path::new("/p/.node_modules.nuking-42")));
+    assert!(is_staged(Path::new("/p/.target.nuking-42-3")));
+    assert!(!is_staged(Path::new("/p/node_modules")));
+    assert!(!is_staged(Path::new("/p/.nuking-42")));
+    assert!(!is_staged(Path::new("/p/.node_modules.nuking-abc")));
+
+    let dead = format!("/p/.node_modules.nuking-{}", DEAD_PID);
+    assert!(is_abandoned(Path::new(&dead)));
+    let ours = format!("/p/.node_modules.nuking-{}", std::process::id());
+    assert!(!is_abandoned(Path::new(&ours)));
+}
+
+#[test]
+fn test_locked_trees_are_not_abandoned() -> Result<()> {
+    // Another dead pid than DEAD_PID, whose lock other tests must not see
+    let pid = DEAD_PID - 1;
+    let staged = format!("/p/.node_modules.nuking-{}", pid);
+
+    // As held by the background deletion that outlived the run
+    let lock = fs::File::create(lock_path(pid))?;
+    lock.lock_shared()?;
+    assert!(!is_abandoned(Path::new(&staged)));
+
+    drop(lock);
+    assert!(is_abandoned(Path::new(&staged)));
+    fs::remove_file(lock_path(pid))?;
+
+    Ok(())
+}
+
+#[test]
+fn test_delete_staged_only_touches_staged_trees() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let staged = temp_dir.path().join(".node_modules.nuking-1");
+    let other = temp_dir.path().join("src");
+    common::create_test_directory_with_content(&staged, 2)?;
+    common::create_test_directory_with_content(&other, 2)?;
+
+    let cleaner = Cleaner::new(Some(2), false);
+    let paths = [staged.clone(), other.clone(), temp_dir.path().join(".gone.nuking-1")];
+    let failures = delete_staged(&paths, cleaner.thread_pool());
+
+    assert!(failures.is_empty());
+    assert!(!staged.exists());
+    assert!(other.exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_fast_cleaner_leaves_deletion_pending() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let target = temp_dir.path().join("project/node_modules");
+    common::create_test_directory_with_content(&target, 3)?;
+
+    let cleaner = Cleaner::new(Some(1), false).with_fast(true);
+    let stats = cleaner.delete_directories(vec![target.clone()])?;
+
+    assert!(!target.exists());
+    assert_eq!(stats.directories_deleted, 0);
+    assert_eq!(stats.directories_staged, 1);
+    assert_eq!(stats.targets[0].status, TargetStatus::Staged);
+    assert_eq!(stats.bytes_freed, 0);
+    assert!(stats.bytes_pending_deletion > 0);
+    assert_eq!(stats.pending_deletion.len(), 1);
+    assert!(stats.pending_deletion[0].exists());
+
+    delete_staged(&stats.pending_deletion, cleaner.thread_pool());
+    assert!(!stats.pending_deletion[0].exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_scanner_reports_leftovers_without_descending() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let leftover = temp_dir.path().join(format!("project/.node_modules.nuking-{}", DEAD_PID));
+    fs::create_dir_all(leftover.join("some-package/node_modules"))?;
+    fs::create_dir_all(temp_dir.path().join("other/node_modules"))?;
+
+    let scanner = Scanner::new(temp_dir.path(), &[]);
+    let targets = scanner.find_node_modules_dirs()?;
+
+    assert_eq!(targets, vec![temp_dir.path().join("other/node_modules")]);
+    assert_eq!(scanner.leftovers(), vec![leftover]);
+
+    Ok(())
+}
+
+#[test]
+fn test_cleanup_finishes_abandoned_leftovers() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let leftover = temp_dir.path().join(format!("project/.node_modules.nuking-{}", DEAD_PID));
+    common::create_test_directory_with_content(&leftover, 2)?;
+
+    let config = Config {
+        quiet: true,
+        no_confirm: true,
+        ..Default::default()
+    };
+    let stats = cleanup_node_modules(temp_dir.path(), &config)?;
+
+    assert_eq!(stats.leftovers_removed, 1);
+    assert!(!leftover.exists());
+    assert!(temp_dir.path().join("project").exists());
+
+    Ok(())
+}
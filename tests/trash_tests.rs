@@ -0,0 +1,233 @@
+//! Unit tests for trash module
+
+use anyhow::Result;
+use nuke_node_modules::cleaner::Cleaner;
+use nuke_node_modules::report::TargetStatus;
+use nuke_node_modules::trash::Trash;
+use nuke_node_modules::{restore_from_trash, Config};
+use std::fs;
+use tempfile::TempDir;
+
+mod common;
+
+#[test]
+fn test_move_to_trash_writes_info_entry() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let trash = Trash::new(temp_dir.path().join("Trash"));
+    let node_modules = temp_dir.path().join("my project/node_modules");
+    common::create_test_directory_with_content(&node_modules, 2)?;
+
+    let destination = trash.move_to_trash(&node_modules)?;
+
+    assert!(!node_modules.exists());
+    assert_eq!(destination, trash.files_dir().join("node_modules"));
+    assert!(destination.join("file_0.txt").exists());
+
+    let info = fs::read_to_string(trash.info_dir().join("node_modules.trashinfo"))?;
+    assert!(info.starts_with("[Trash Info]\n"));
+    assert!(info.contains("/my%20project/node_modules\n"));
+    assert!(info.contains("DeletionDate="));
+
+    Ok(())
+}
+
+#[test]
+fn test_move_to_trash_avoids_name_collisions() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let trash = Trash::new(temp_dir.path().join("Trash"));
+
+    let first = temp_dir.path().join("project1/node_modules");
+    let second = temp_dir.path().join("project2/node_modules");
+    fs::create_dir_all(&first)?;
+    fs::create_dir_all(&second)?;
+
+    let first_dest = trash.move_to_trash(&first)?;
+    let second_dest = trash.move_to_trash(&second)?;
+
+    assert_ne!(first_dest, second_dest);
+    assert_eq!(second_dest, trash.files_dir().join("node_modules.2"));
+    assert!(trash.info_dir().join("node_modules.2.trashinfo").exists());
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_move_to_trash_skips_dangling_symlinks() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let trash = Trash::new(temp_dir.path().join("Trash"));
+    fs::create_dir_all(trash.files_dir())?;
+    std::os::unix::fs::symlink(temp_dir.path().join("missing"), trash.files_dir().join("node_modules"))?;
+
+    let node_modules = temp_dir.path().join("project/node_modules");
+    fs::create_dir_all(&node_modules)?;
+
+    let destination = trash.move_to_trash(&node_modules)?;
+
+    assert_eq!(destination, trash.files_dir().join("node_modules.2"));
+    assert!(fs::symlink_metadata(trash.files_dir().join("node_modules"))?.file_type().is_symlink());
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_trash_round_trip_non_utf8_names() -> Result<()> {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let temp_dir = TempDir::new()?;
+    let trash = Trash::new(temp_dir.path().join("Trash"));
+    let name = OsStr::from_bytes(b"caf\xe9");
+    let target = temp_dir.path().join(name).join(name);
+    common::create_test_directory_with_content(&target, 1)?;
+
+    let destination = trash.move_to_trash(&target)?;
+    assert_eq!(destination, trash.files_dir().join(name));
+
+    let items = trash.list()?;
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].original, target);
+
+    trash.restore(&items[0])?;
+    assert!(target.join("file_0.txt").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_move_to_trash_nonexistent_path_cleans_up_info() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let trash = Trash::new(temp_dir.path().join("Trash"));
+
+    let result = trash.move_to_trash(&temp_dir.path().join("missing/node_modules"));
+
+    assert!(result.is_err());
+    assert!(!trash.info_dir().join("node_modules.trashinfo").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_cleaner_reports_trashed_separately() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let trash = Trash::new(temp_dir.path().join("Trash"));
+
+    let targets = vec![
+        temp_dir.path().join("project1/node_modules"),
+        temp_dir.path().join("project2/node_modules"),
+    ];
+    for target in &targets {
+        common::create_test_directory_with_content(target, 2)?;
+    }
+
+    let cleaner = Cleaner::new(Some(2), false).with_trash(Some(trash.clone()));
+    let stats = cleaner.delete_directories(targets.clone())?;
+
+    assert_eq!(stats.directories_found, 2);
+    assert_eq!(stats.directories_trashed, 2);
+    assert_eq!(stats.directories_deleted, 0);
+    assert_eq!(stats.bytes_freed, 0);
+    assert!(stats.bytes_trashed > 0);
+
+    for target in &targets {
+        assert!(!target.exists());
+    }
+    assert_eq!(fs::read_dir(trash.files_dir())?.count(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_list_and_restore_round_trip() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let trash = Trash::new(temp_dir.path().join("Trash"));
+    let node_modules = temp_dir.path().join("my project/node_modules");
+    common::create_test_directory_with_content(&node_modules, 2)?;
+
+    trash.move_to_trash(&node_modules)?;
+    let items = trash.list()?;
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].original, node_modules);
+    assert!(items[0].deleted_at.is_some());
+
+    trash.restore(&items[0])?;
+    assert!(node_modules.join("file_0.txt").exists());
+    assert!(trash.list()?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_restore_refuses_to_overwrite() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let trash = Trash::new(temp_dir.path().join("Trash"));
+    let node_modules = temp_dir.path().join("project/node_modules");
+    fs::create_dir_all(&node_modules)?;
+
+    trash.move_to_trash(&node_modules)?;
+    fs::create_dir_all(&node_modules)?;
+
+    let items = trash.list()?;
+    assert!(trash.restore(&items[0]).is_err());
+    assert!(items[0].location.exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_restore_from_trash_only_restores_targets_below_root() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let trash = Trash::new(temp_dir.path().join("Trash"));
+    let inside = temp_dir.path().join("projects/app/node_modules");
+    let outside = temp_dir.path().join("elsewhere/node_modules");
+    let not_a_target = temp_dir.path().join("projects/app/notes");
+    for dir in [&inside, &outside, &not_a_target] {
+        fs::create_dir_all(dir)?;
+        trash.move_to_trash(dir)?;
+    }
+
+    let root = temp_dir.path().join("projects");
+    let dry_run = Config { dry_run: true, ..Default::default() };
+    let outcomes = restore_from_trash(&trash, &root, &dry_run)?;
+    assert_eq!(outcomes.len(), 1);
+    assert_eq!(outcomes[0].status, TargetStatus::WouldRestore);
+    assert!(!inside.exists());
+
+    let outcomes = restore_from_trash(&trash, &root, &Config::default())?;
+    assert_eq!(outcomes[0].status, TargetStatus::Restored);
+    assert!(inside.exists());
+    assert!(!outside.exists());
+    assert!(!not_a_target.exists());
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_volume_trash_is_at_the_top_of_the_volume() -> Result<()> {
+    use std::os::unix::fs::MetadataExt;
+
+    let temp_dir = TempDir::new()?;
+    let project = temp_dir.path().join("project");
+    fs::create_dir_all(&project)?;
+
+    let trash = Trash::for_volume_of(&project).expect("volume trash");
+    // SAFETY: getuid has no preconditions and cannot fail
+    let uid = unsafe { libc::getuid() };
+    let top = if trash.root().ends_with(format!(".Trash/{}", uid)) {
+        trash.root().parent().and_then(|shared| shared.parent())
+    } else {
+        assert!(trash.root().ends_with(format!(".Trash-{}", uid)));
+        trash.root().parent()
+    }
+    .expect("top directory");
+
+    // The top directory is the highest one on the project's filesystem
+    let device = fs::metadata(&project)?.dev();
+    assert!(std::path::absolute(&project)?.starts_with(top));
+    assert_eq!(fs::metadata(top)?.dev(), device);
+    assert!(top.parent().is_none_or(|above| fs::metadata(above).is_ok_and(|m| m.dev() != device)));
+
+    Ok(())
+}
@@ -0,0 +1,117 @@
+//! Unit tests for workspace module
+
+use anyhow::Result;
+use nuke_node_modules::workspace::{
+    package_json_workspaces, partial_workspaces, pnpm_packages, Workspace, WorkspaceIndex,
+};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// Create a package directory with a package.json and a node_modules
+fn create_package(dir: &Path) -> Result<()> {
+    fs::create_dir_all(dir.join("node_modules"))?;
+    fs::write(dir.join("package.json"), "{}")?;
+    Ok(())
+}
+
+#[test]
+fn test_pnpm_packages_block_and_flow_lists() {
+    let yaml = "\
+# workspace layout
+packages:
+  - 'apps/*'
+  - \"packages/**\"   # everything below
+  - tools/cli
+  - '!**/test/**'
+catalog:
+  react: ^18
+";
+    assert_eq!(pnpm_packages(yaml), vec!["apps/*", "packages/**", "tools/cli", "!**/test/**"]);
+
+    assert_eq!(pnpm_packages("packages: ['apps/*', \"libs/*\"]\n"), vec!["apps/*", "libs/*"]);
+    assert!(pnpm_packages("catalog:\n  - 'apps/*'\n").is_empty());
+}
+
+#[test]
+fn test_package_json_workspaces_forms() {
+    assert_eq!(
+        package_json_workspaces(r#"{"workspaces": ["packages/*"]}"#),
+        Some(vec!["packages/*".to_string()])
+    );
+    assert_eq!(
+        package_json_workspaces(r#"{"workspaces": {"packages": ["apps/*"], "nohoist": ["**/x"]}}"#),
+        Some(vec!["apps/*".to_string()])
+    );
+    assert_eq!(package_json_workspaces(r#"{"name": "app"}"#), None);
+    assert_eq!(package_json_workspaces("not json"), None);
+}
+
+#[test]
+fn test_load_finds_members_matching_patterns() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let root = temp_dir.path();
+    fs::write(root.join("pnpm-workspace.yaml"), "packages:\n  - 'packages/*'\n  - '!packages/legacy'\n")?;
+    create_package(&root.join("packages/a"))?;
+    create_package(&root.join("packages/b"))?;
+    create_package(&root.join("packages/legacy"))?;
+    // Too deep for `packages/*`, and not a package at all
+    create_package(&root.join("packages/a/fixtures/c"))?;
+    fs::create_dir_all(root.join("packages/docs"))?;
+
+    let workspace = Workspace::load(root).expect("workspace");
+
+    assert_eq!(workspace.members, vec![root.join("packages/a"), root.join("packages/b")]);
+    assert!(workspace.contains(root));
+    assert!(workspace.contains(&root.join("packages/b")));
+    assert!(!workspace.contains(&root.join("packages/legacy")));
+
+    Ok(())
+}
+
+#[test]
+fn test_workspace_of_looks_above_the_project() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let root = temp_dir.path();
+    fs::write(root.join("package.json"), r#"{"workspaces": ["apps/*"]}"#)?;
+    create_package(&root.join("apps/web"))?;
+    create_package(&root.join("examples/demo"))?;
+
+    let mut index = WorkspaceIndex::new();
+    let workspace = index.workspace_of(&root.join("apps/web")).map(|w| w.root.clone());
+    assert_eq!(workspace, Some(root.to_path_buf()));
+    assert!(index.workspace_of(&root.join("examples/demo")).is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_partial_workspaces() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let root = temp_dir.path().join("mono");
+    create_package(&root)?;
+    fs::write(root.join("package.json"), r#"{"workspaces": ["packages/*"]}"#)?;
+    create_package(&root.join("packages/a"))?;
+    create_package(&root.join("packages/b"))?;
+    create_package(&temp_dir.path().join("standalone"))?;
+
+    let whole = vec![
+        root.join("node_modules"),
+        root.join("packages/a/node_modules"),
+        root.join("packages/b/node_modules"),
+        temp_dir.path().join("standalone/node_modules"),
+    ];
+    assert!(partial_workspaces(&whole).is_empty());
+
+    let partial = partial_workspaces(&whole[1..]);
+    assert_eq!(partial.len(), 1);
+    assert_eq!(partial[0].root, root);
+    assert_eq!(partial[0].selected, vec![root.join("packages/a/node_modules"), root.join("packages/b/node_modules")]);
+    assert_eq!(partial[0].missing, vec![root.join("node_modules")]);
+
+    // A member whose node_modules doesn't exist doesn't count as missing
+    fs::remove_dir_all(root.join("packages/b/node_modules"))?;
+    assert!(partial_workspaces(&whole[..2]).is_empty());
+
+    Ok(())
+}